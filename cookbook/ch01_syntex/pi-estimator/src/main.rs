@@ -1,19 +1,309 @@
-mod rounding;
+mod cli {
+    use clap::{Parser, ValueEnum};
+
+    use rust_pilib::RoundingMode;
+
+    #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+    pub enum Algorithm {
+        MonteCarlo,
+        Leibniz,
+        Nilakantha,
+        Machin,
+        Bbp,
+        All,
+    }
+    impl Algorithm {
+        ///
+        /// Whether the estimator called `name` was selected.
+        ///
+        pub fn includes(&self, name: &str) -> bool {
+            *self == Algorithm::All
+                || self.to_possible_value().is_some_and(|v| v.get_name() == name)
+        }
+    }
+
+    ///
+    /// The rounding modes of `rust_pilib::RoundingMode`, as command-line values.
+    ///
+    #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+    pub enum Rounding {
+        /// Ties go towards positive infinity (2.5 -> 3, -2.5 -> -2)
+        HalfUp,
+        /// Ties go to the even neighbour (2.5 -> 2, 3.5 -> 4)
+        HalfEven,
+        /// Ties go away from zero (2.5 -> 3, -2.5 -> -3)
+        HalfAwayFromZero,
+        /// Towards negative infinity
+        Floor,
+        /// Towards positive infinity
+        Ceil,
+        /// Towards zero
+        Truncate,
+    }
+    impl From<Rounding> for RoundingMode {
+        fn from(rounding: Rounding) -> RoundingMode {
+            match rounding {
+                Rounding::HalfUp => RoundingMode::HalfUp,
+                Rounding::HalfEven => RoundingMode::HalfEven,
+                Rounding::HalfAwayFromZero => RoundingMode::HalfAwayFromZero,
+                Rounding::Floor => RoundingMode::Floor,
+                Rounding::Ceil => RoundingMode::Ceil,
+                Rounding::Truncate => RoundingMode::Truncate,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+    pub enum Format {
+        Human,
+        Json,
+        Csv,
+    }
+
+    ///
+    /// Approximates pi with a choice of algorithms.
+    ///
+    #[derive(Debug, Parser)]
+    #[command(version)]
+    pub struct Args {
+        /// Iterations (series terms, or points for Monte Carlo)
+        #[arg(short = 'n', long, default_value_t = 100_000)]
+        pub iterations: usize,
+
+        /// Decimal places to round the estimate to
+        #[arg(short, long, default_value_t = 2)]
+        pub places: usize,
+
+        /// How to round the estimate to the decimal places
+        #[arg(short, long, value_enum, default_value_t = Rounding::HalfUp)]
+        pub rounding: Rounding,
+
+        #[arg(short, long, value_enum, default_value_t = Algorithm::MonteCarlo)]
+        pub algorithm: Algorithm,
+
+        /// Seed for Monte Carlo; random if omitted
+        #[arg(short, long)]
+        pub seed: Option<u64>,
+
+        /// Worker threads for Monte Carlo; one per core if omitted
+        #[arg(short, long)]
+        pub threads: Option<usize>,
+
+        #[arg(short, long, value_enum, default_value_t = Format::Human)]
+        pub format: Format,
+
+        /// Print estimates for 10, 100, 1000, ... up to the iterations
+        #[arg(short, long)]
+        pub convergence: bool,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn rounding_maps_to_library_modes() {
+            let args = Args::try_parse_from(["pi-estimator", "--rounding", "half-even"]).unwrap();
+            assert_eq!(RoundingMode::from(args.rounding), RoundingMode::HalfEven);
+
+            let args = Args::try_parse_from(["pi-estimator"]).unwrap();
+            assert_eq!(RoundingMode::from(args.rounding), RoundingMode::default());
+            assert!(Args::try_parse_from(["pi-estimator", "-r", "sideways"]).is_err());
+        }
+    }
+}
 
 mod printer {
-    use rust_pilib::monte_carlo_pi;
-    use crate::rounding::round;
-    
-    pub fn pretty_print_pi_approx(iterations: usize) {
-        let pi = monte_carlo_pi(iterations);
-        let places: usize = 2;
+    use rust_pilib::{round_exact, PiEstimator, RoundingMode};
+    use serde::Serialize;
+    use std::time::Instant;
 
-        println!("Pi is ~ {} and rounded to {} places {}", pi, places, round(pi, places));
+    use crate::cli::Format;
+
+    ///
+    /// One estimate, as printed in every format.
+    ///
+    #[derive(Debug, Serialize)]
+    pub struct Row {
+        pub algorithm: &'static str,
+        pub iterations: usize,
+        pub estimate: f64,
+        pub rounded: f64,
+        pub error: f64,
+        pub seconds: f64,
+    }
+    impl Row {
+        pub fn run(
+            estimator: &dyn PiEstimator,
+            iterations: usize,
+            places: usize,
+            mode: RoundingMode,
+        ) -> Row {
+            let start = Instant::now();
+            let estimate = estimator.estimate(iterations);
+            let seconds = start.elapsed().as_secs_f64();
+
+            Row {
+                algorithm: estimator.name(),
+                iterations,
+                estimate,
+                rounded: round_exact(estimate, places, mode),
+                error: (estimate - std::f64::consts::PI).abs(),
+                seconds,
+            }
+        }
+    }
+
+    ///
+    /// 10, 100, 1000, ... below `iterations`, followed by `iterations`.
+    ///
+    pub fn convergence_steps(iterations: usize) -> Vec<usize> {
+        let mut steps: Vec<usize> = std::iter::successors(Some(10_usize), |s| s.checked_mul(10))
+            .take_while(|s| *s < iterations)
+            .collect();
+        steps.push(iterations);
+        steps
+    }
+
+    ///
+    /// Decimal places shown in the table; an `f64` near pi has no more.
+    ///
+    const MAX_SHOWN_PLACES: usize = 17;
+
+    pub fn format_rows(rows: &[Row], format: Format, places: usize) -> String {
+        match format {
+            Format::Json => serde_json::to_string_pretty(rows).expect("rows are serializable") + "\n",
+            Format::Csv => {
+                let mut out = String::from("algorithm,iterations,estimate,rounded,error,seconds\n");
+                for r in rows {
+                    out += &format!(
+                        "{},{},{},{},{},{}\n",
+                        r.algorithm, r.iterations, r.estimate, r.rounded, r.error, r.seconds
+                    );
+                }
+                out
+            }
+            Format::Human if rows.len() == 1 => {
+                let r = &rows[0];
+                format!("Pi is ~ {} and rounded to {} places {}\n", r.estimate, places, r.rounded)
+            }
+            Format::Human => {
+                let shown = places.min(MAX_SHOWN_PLACES);
+                let width = (shown + 3).max(8);
+                let mut out = format!(
+                    "{:<12} {:>12} {:>20} {:>width$} {:>12} {:>12}\n",
+                    "algorithm", "iterations", "estimate", "rounded", "error", "seconds"
+                );
+                for r in rows {
+                    out += &format!(
+                        "{:<12} {:>12} {:>20.15} {:>width$.shown$} {:>12.3e} {:>12.6}\n",
+                        r.algorithm, r.iterations, r.estimate, r.rounded, r.error, r.seconds
+                    );
+                }
+                out
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn row() -> Row {
+            Row {
+                algorithm: "leibniz",
+                iterations: 10,
+                estimate: 3.0418396189294032,
+                rounded: 3.04,
+                error: 0.09975303466038987,
+                seconds: 0.5,
+            }
+        }
+
+        #[test]
+        fn convergence_steps_end_at_iterations() {
+            assert_eq!(convergence_steps(1000), vec![10, 100, 1000]);
+            assert_eq!(convergence_steps(2500), vec![10, 100, 1000, 2500]);
+            assert_eq!(convergence_steps(5), vec![5]);
+        }
+
+        #[test]
+        fn format_rows_as_csv_and_json() {
+            assert_eq!(
+                format_rows(&[row()], Format::Csv, 2),
+                "algorithm,iterations,estimate,rounded,error,seconds\n\
+                 leibniz,10,3.0418396189294032,3.04,0.09975303466038987,0.5\n"
+            );
+
+            let json: serde_json::Value =
+                serde_json::from_str(&format_rows(&[row()], Format::Json, 2)).unwrap();
+            assert_eq!(json[0]["algorithm"], "leibniz");
+            assert_eq!(json[0]["iterations"], 10);
+        }
+
+        #[test]
+        fn format_single_row_as_sentence() {
+            assert_eq!(
+                format_rows(&[row()], Format::Human, 2),
+                "Pi is ~ 3.0418396189294032 and rounded to 2 places 3.04\n"
+            );
+        }
+
+        #[test]
+        fn format_rows_as_table() {
+            let rows = [
+                row(),
+                Row { iterations: 100, estimate: 3.1315929035585537, rounded: 3.13, ..row() },
+            ];
+            assert_eq!(
+                format_rows(&rows, Format::Human, 2),
+                "algorithm      iterations             estimate  rounded        error      seconds\n\
+                 leibniz                10    3.041839618929403     3.04     9.975e-2     0.500000\n\
+                 leibniz               100    3.131592903558554     3.13     9.975e-2     0.500000\n"
+            );
+
+            let table = format_rows(&rows, Format::Human, 5);
+            assert!(table.lines().nth(1).unwrap().contains(" 3.04000 "));
+            let table = format_rows(&rows, Format::Human, usize::MAX);
+            assert!(table.lines().nth(1).unwrap().contains(" 3.04000000000000004 "));
+        }
     }
 }
 
-use printer::pretty_print_pi_approx;
+use clap::Parser;
+use cli::Args;
+use printer::{convergence_steps, format_rows, Row};
+use rust_pilib::estimators;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
-    pretty_print_pi_approx(100_000);
+    let args = Args::parse();
+
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .expect("Couldn't configure the thread pool");
+    }
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    let mode = args.rounding.into();
+    let steps = if args.convergence {
+        convergence_steps(args.iterations)
+    } else {
+        vec![args.iterations]
+    };
+
+    let rows: Vec<Row> = estimators(seed)
+        .iter()
+        .filter(|e| args.algorithm.includes(e.name()))
+        .flat_map(|e| steps.iter().map(move |&n| Row::run(e.as_ref(), n, args.places, mode)))
+        .collect();
+
+    print!("{}", format_rows(&rows, args.format, args.places));
 }
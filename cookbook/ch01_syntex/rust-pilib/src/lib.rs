@@ -1,12 +1,31 @@
 use rand::prelude::*;
+use rand::prng::ChaChaRng;
+
+mod parallel;
+mod rounding;
+mod series;
+
+pub use parallel::{
+    par_monte_carlo_pi, par_monte_carlo_pi_until, MonteCarloError, PiEstimate, MIN_ITERATIONS, Z_95,
+};
+pub use rounding::{round, round_decimal, round_exact, round_with, ParseDecimalError, Roundable, RoundingMode};
+pub use series::{bbp_hex_digits, estimators, Bbp, Leibniz, Machin, MonteCarlo, Nilakantha, PiEstimator};
 
 pub fn monte_carlo_pi(iterations: usize) -> f32 {
+    monte_carlo_pi_with_rng(iterations, &mut thread_rng())
+}
+
+///
+/// Estimates pi with random numbers drawn from `rng`, so passing a
+/// seeded generator makes the result reproducible.
+///
+pub fn monte_carlo_pi_with_rng<R: Rng + ?Sized>(iterations: usize, rng: &mut R) -> f32 {
     let mut inside_circle = 0;
     
     for _ in 0..iterations {
         // generate two rand
-        let x: f32 = random::<f32>();
-        let y: f32 = random::<f32>();
+        let x: f32 = rng.gen::<f32>();
+        let y: f32 = rng.gen::<f32>();
 
         // calculate the circular distance from 0,0
         if x.powi(2) + y.powi(2) <= 1_f32 {
@@ -18,14 +37,18 @@ pub fn monte_carlo_pi(iterations: usize) -> f32 {
     (4_f32 * inside_circle as f32) / iterations as f32
 }
 
+///
+/// Estimates pi with a ChaCha generator seeded with `seed`. The same seed
+/// always gives the same result, on every platform.
+///
+pub fn monte_carlo_pi_seeded(iterations: usize, seed: u64) -> f32 {
+    monte_carlo_pi_with_rng(iterations, &mut ChaChaRng::seed_from_u64(seed))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn is_reasonably_pi(pi: f32) -> bool {
-        pi >= 3_f32 && pi <= 4.5_f32
-    }
-
     #[test]
     fn test_monte_carlo_pi_1() {
         let pi = monte_carlo_pi(1);
@@ -34,19 +57,35 @@ mod tests {
 
     #[test]
     fn test_monte_carlo_pi_500() {
-        let pi = monte_carlo_pi(500);
-        assert!(is_reasonably_pi(pi))
+        assert_eq!(monte_carlo_pi_seeded(500, 7), 3.192);
     }
 
     #[test]
     fn test_monte_carlo_pi_1000() {
-        let pi = monte_carlo_pi(1000);
-        assert!(is_reasonably_pi(pi))
+        assert_eq!(monte_carlo_pi_seeded(1000, 7), 3.176);
     }
 
     #[test]
     fn test_monte_carlo_pi_5000() {
-        let pi = monte_carlo_pi(5000);
-        assert!(is_reasonably_pi(pi))
+        assert_eq!(monte_carlo_pi_seeded(5000, 7), 3.1368);
+    }
+
+    #[test]
+    fn test_monte_carlo_pi_seeded() {
+        assert_eq!(monte_carlo_pi_seeded(1, 42), 4_f32);
+        assert_eq!(monte_carlo_pi_seeded(500, 42), 3.208);
+        assert_eq!(monte_carlo_pi_seeded(5000, 42), 3.1312);
+        assert_eq!(monte_carlo_pi_seeded(100_000, 3), 3.14124);
+    }
+
+    #[test]
+    fn test_monte_carlo_pi_with_rng() {
+        let mut rng = ChaChaRng::seed_from_u64(42);
+        assert_eq!(monte_carlo_pi_with_rng(5000, &mut rng), 3.1312);
+        // the generator continues where it left off
+        assert_ne!(monte_carlo_pi_with_rng(5000, &mut rng), 3.1312);
+
+        let rng: &mut dyn RngCore = &mut ChaChaRng::seed_from_u64(42);
+        assert_eq!(monte_carlo_pi_with_rng(5000, rng), 3.1312);
     }
 }
@@ -0,0 +1,237 @@
+use rand::prelude::*;
+use rand::prng::ChaChaRng;
+use rayon::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+///
+/// The z-score of a two-sided 95% confidence interval.
+///
+pub const Z_95: f64 = 1.959_963_984_540_054;
+
+///
+/// Points needed before the standard error is trusted for stopping. With
+/// fewer, all points may land inside (or outside) the circle, giving a
+/// standard error of 0.
+///
+pub const MIN_ITERATIONS: usize = 1_000;
+
+///
+/// Points drawn by one stream at most; work is split into chunks of this
+/// size so that it spreads over all threads.
+///
+const CHUNK: usize = 16_384;
+
+///
+/// A Monte Carlo estimate of pi with its statistical uncertainty.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PiEstimate {
+    pub pi: f64,
+    pub std_error: f64,
+    pub iterations: usize,
+}
+impl PiEstimate {
+    ///
+    /// Each point is a Bernoulli trial with p = pi / 4, so the estimate
+    /// 4 * hits / n has a standard error of 4 * sqrt(p * (1 - p) / n).
+    ///
+    fn from_hits(hits: usize, iterations: usize) -> PiEstimate {
+        let n = iterations as f64;
+        let p = hits as f64 / n;
+        PiEstimate {
+            pi: 4.0 * p,
+            std_error: 4.0 * (p * (1.0 - p) / n).sqrt(),
+            iterations,
+        }
+    }
+
+    ///
+    /// The interval `pi ± z * std_error`, e.g. with `Z_95`.
+    ///
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        (self.pi - z * self.std_error, self.pi + z * self.std_error)
+    }
+}
+
+///
+/// Why a Monte Carlo estimate couldn't be made.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MonteCarloError {
+    /// Without points there is nothing to estimate from
+    NoIterations,
+    /// The target standard error wasn't reached within the allowed
+    /// points; holds the estimate from all points drawn
+    TargetNotReached(PiEstimate),
+}
+impl fmt::Display for MonteCarloError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonteCarloError::NoIterations => write!(f, "no iterations to estimate pi from"),
+            MonteCarloError::TargetNotReached(estimate) => write!(
+                f,
+                "standard error {} after {} iterations is above the target",
+                estimate.std_error, estimate.iterations
+            ),
+        }
+    }
+}
+impl Error for MonteCarloError {}
+
+///
+/// Counts the hits of `iterations` points drawn from stream `stream` of
+/// the ChaCha generator seeded with `seed`. Streams never overlap.
+///
+fn stream_hits(seed: u64, stream: u64, iterations: usize) -> usize {
+    let mut rng = ChaChaRng::seed_from_u64(seed);
+    rng.set_stream(stream);
+
+    (0..iterations)
+        .filter(|_| {
+            let x: f64 = rng.gen();
+            let y: f64 = rng.gen();
+            x * x + y * y <= 1_f64
+        })
+        .count()
+}
+
+///
+/// Draws `iterations` points in parallel, using the streams starting at
+/// `first_stream`. Returns the hits and the number of streams used.
+///
+fn par_hits(iterations: usize, seed: u64, first_stream: u64) -> (usize, u64) {
+    let chunks = iterations.div_ceil(CHUNK);
+    let hits = (0..chunks)
+        .into_par_iter()
+        .map(|i| {
+            let len = CHUNK.min(iterations - i * CHUNK);
+            stream_hits(seed, first_stream + i as u64, len)
+        })
+        .sum();
+    (hits, chunks as u64)
+}
+
+///
+/// Estimates pi from `iterations` points, spread over rayon's threads.
+///
+/// The points are split into fixed-size chunks, each drawn from its own
+/// stream of a generator seeded with `seed`, so the result only depends
+/// on `iterations` and `seed`, not on the number of threads.
+///
+pub fn par_monte_carlo_pi(iterations: usize, seed: u64) -> Result<PiEstimate, MonteCarloError> {
+    if iterations == 0 {
+        return Err(MonteCarloError::NoIterations);
+    }
+    let (hits, _) = par_hits(iterations, seed, 0);
+    Ok(PiEstimate::from_hits(hits, iterations))
+}
+
+///
+/// Draws points in rounds until the standard error is at most
+/// `target_std_error`, or `max_iterations` points have been drawn.
+///
+/// Each round is sized from the current estimate to just reach the
+/// target, and uses fresh streams so all points stay independent. The
+/// target only counts as reached after at least `MIN_ITERATIONS` points.
+///
+pub fn par_monte_carlo_pi_until(
+    target_std_error: f64,
+    max_iterations: usize,
+    seed: u64,
+) -> Result<PiEstimate, MonteCarloError> {
+    if max_iterations == 0 {
+        return Err(MonteCarloError::NoIterations);
+    }
+    let first_round = CHUNK.max(MIN_ITERATIONS).min(max_iterations);
+    let (mut hits, mut streams) = par_hits(first_round, seed, 0);
+    let mut iterations = first_round;
+
+    loop {
+        let estimate = PiEstimate::from_hits(hits, iterations);
+        if iterations >= MIN_ITERATIONS && estimate.std_error <= target_std_error {
+            return Ok(estimate);
+        }
+        if iterations >= max_iterations {
+            return Err(MonteCarloError::TargetNotReached(estimate));
+        }
+
+        // n = 16 p (1 - p) / se^2, see `PiEstimate::from_hits`
+        let p = hits as f64 / iterations as f64;
+        let needed = (16.0 * p * (1.0 - p) / target_std_error.powi(2)).ceil() as usize;
+        let round = needed
+            .saturating_sub(iterations)
+            .max(CHUNK)
+            .min(max_iterations - iterations);
+
+        let (round_hits, round_streams) = par_hits(round, seed, streams);
+        hits += round_hits;
+        streams += round_streams;
+        iterations += round;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_monte_carlo_pi_is_reproducible() {
+        let estimate = par_monte_carlo_pi(1_000_000, 42).unwrap();
+        assert_eq!(Ok(estimate), par_monte_carlo_pi(1_000_000, 42));
+        assert_ne!(estimate.pi, par_monte_carlo_pi(1_000_000, 43).unwrap().pi);
+        assert_eq!(estimate.iterations, 1_000_000);
+
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        assert_eq!(pool.install(|| par_monte_carlo_pi(1_000_000, 42)), Ok(estimate));
+    }
+
+    #[test]
+    fn test_par_monte_carlo_pi_confidence_interval() {
+        let estimate = par_monte_carlo_pi(1_000_000, 42).unwrap();
+        let (low, high) = estimate.confidence_interval(Z_95);
+
+        assert!(low < std::f64::consts::PI && std::f64::consts::PI < high);
+        assert!((estimate.std_error - 0.001642).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_par_monte_carlo_pi_no_iterations() {
+        assert_eq!(par_monte_carlo_pi(0, 42), Err(MonteCarloError::NoIterations));
+        assert_eq!(par_monte_carlo_pi_until(0.1, 0, 42), Err(MonteCarloError::NoIterations));
+    }
+
+    #[test]
+    fn test_par_monte_carlo_pi_until_precision() {
+        let estimate = par_monte_carlo_pi_until(0.002, 10_000_000, 7).unwrap();
+        assert!(estimate.std_error <= 0.002);
+        assert!(estimate.iterations < 1_000_000);
+        assert!((estimate.pi - std::f64::consts::PI).abs() < 4.0 * 0.002);
+        assert_eq!(Ok(estimate), par_monte_carlo_pi_until(0.002, 10_000_000, 7));
+    }
+
+    #[test]
+    fn test_par_monte_carlo_pi_until_max_iterations() {
+        match par_monte_carlo_pi_until(1e-9, 100_000, 7) {
+            Err(MonteCarloError::TargetNotReached(estimate)) => {
+                assert_eq!(estimate.iterations, 100_000);
+                assert!(estimate.std_error > 1e-9);
+            }
+            other => panic!("expected the target not to be reached, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_par_monte_carlo_pi_until_needs_min_iterations() {
+        // a single point is always a hit or a miss, with a standard error of 0
+        let single = par_monte_carlo_pi(1, 7).unwrap();
+        assert_eq!(single.std_error, 0.0);
+
+        assert_eq!(
+            par_monte_carlo_pi_until(0.1, 1, 7),
+            Err(MonteCarloError::TargetNotReached(single))
+        );
+        let estimate = par_monte_carlo_pi_until(0.1, 10_000_000, 7).unwrap();
+        assert!(estimate.iterations >= MIN_ITERATIONS);
+    }
+}
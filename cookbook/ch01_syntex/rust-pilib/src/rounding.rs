@@ -0,0 +1,310 @@
+//!
+//! Rounding to decimal places, either in binary floating point or on the
+//! decimal digits as written.
+//!
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+///
+/// How to round a value that lies between two candidates.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Ties go towards positive infinity (2.5 -> 3, -2.5 -> -2).
+    #[default]
+    HalfUp,
+    /// Ties go to the even neighbour (2.5 -> 2, 3.5 -> 4), a.k.a. banker's rounding.
+    HalfEven,
+    /// Ties go away from zero (2.5 -> 3, -2.5 -> -3).
+    HalfAwayFromZero,
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards zero.
+    Truncate,
+}
+
+///
+/// Floating point types that can be rounded to decimal places. The
+/// arithmetic is done in `f64`, which holds every `f32` exactly.
+///
+pub trait Roundable: Copy + fmt::Display + FromStr {
+    fn to_f64(self) -> f64;
+    fn from_f64(nr: f64) -> Self;
+}
+macro_rules! roundable {
+    ($($t:ty),*) => {
+        $(impl Roundable for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(nr: f64) -> Self {
+                nr as $t
+            }
+        })*
+    };
+}
+roundable!(f32, f64);
+
+///
+/// Beyond this magnitude every `f64` is an integer, so there is nothing
+/// left to round.
+///
+const EXACT_INTEGER_LIMIT: f64 = 4_503_599_627_370_496.0; // 2^52
+
+pub fn round<T: Roundable>(nr: T, places: usize) -> T {
+    round_with(nr, places, RoundingMode::HalfUp)
+}
+
+///
+/// Rounds `nr` to `places` decimal places in binary floating point.
+///
+/// Values like 1.005 are stored as 1.00499999..., so they round down
+/// here; use `round_exact` to round the decimal number as written.
+/// Values that have no digits at `places` (e.g. because `places` is
+/// very large) are returned unchanged.
+///
+pub fn round_with<T: Roundable>(nr: T, places: usize, mode: RoundingMode) -> T {
+    let value = nr.to_f64();
+    if places > f64::MAX_10_EXP as usize {
+        return nr;
+    }
+    let multiplier = 10_f64.powi(places as i32);
+    let scaled = value * multiplier;
+    if !scaled.is_finite() || scaled.abs() >= EXACT_INTEGER_LIMIT {
+        return nr;
+    }
+
+    let rounded = match mode {
+        RoundingMode::HalfUp => (scaled + 0.5).floor(),
+        RoundingMode::HalfEven => scaled.round_ties_even(),
+        RoundingMode::HalfAwayFromZero => scaled.round(),
+        RoundingMode::Floor => scaled.floor(),
+        RoundingMode::Ceil => scaled.ceil(),
+        RoundingMode::Truncate => scaled.trunc(),
+    };
+    T::from_f64(rounded / multiplier)
+}
+
+///
+/// Rounds the shortest decimal representation of `nr`, so 1.005 rounds
+/// to 1.01 with `HalfUp` as it would on paper. The result is the float
+/// closest to the rounded decimal.
+///
+/// Like `round_with`, values that have no digits at `places` are returned
+/// unchanged, so a huge `places` costs no more than the digits of `nr`.
+///
+pub fn round_exact<T: Roundable>(nr: T, places: usize, mode: RoundingMode) -> T {
+    let decimal = nr.to_string();
+    let digits_after_point = decimal.split_once('.').map_or(0, |(_, frac)| frac.len());
+    match round_decimal(&decimal, places.min(digits_after_point), mode).map(|s| s.parse()) {
+        Ok(Ok(rounded)) => rounded,
+        // NaN and infinities
+        _ => nr,
+    }
+}
+
+///
+/// The input of `round_decimal` wasn't a plain decimal number.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError {
+    input: String,
+}
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a decimal number like `-12.345`", self.input)
+    }
+}
+impl Error for ParseDecimalError {}
+
+///
+/// Rounds a decimal number given as text, digit by digit, and returns it
+/// with exactly `places` decimals, e.g. `("2.675", 2)` gives `"2.68"`.
+///
+pub fn round_decimal(nr: &str, places: usize, mode: RoundingMode) -> Result<String, ParseDecimalError> {
+    let error = || ParseDecimalError { input: nr.to_string() };
+
+    let (negative, unsigned) = match nr.trim() {
+        s if s.starts_with('-') => (true, &s[1..]),
+        s => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int_part.is_empty() || !is_digits(int_part) || !is_digits(frac_part) {
+        return Err(error());
+    }
+
+    // all digits up to `places`, padded with zeros, and the ones after it
+    let mut digits: Vec<u8> = int_part.bytes().map(|b| b - b'0').collect();
+    let kept = frac_part.len().min(places);
+    digits.extend(frac_part[..kept].bytes().map(|b| b - b'0'));
+    digits.extend(std::iter::repeat_n(0, places - kept));
+    let rest = &frac_part[kept..];
+
+    let rest_nonzero = rest.bytes().any(|b| b != b'0');
+    let half = match rest.as_bytes().first() {
+        None => std::cmp::Ordering::Less,
+        Some(&first) if first != b'5' => first.cmp(&b'5'),
+        Some(_) if rest[1..].bytes().any(|b| b != b'0') => std::cmp::Ordering::Greater,
+        Some(_) => std::cmp::Ordering::Equal,
+    };
+    let last_odd = digits.last().is_some_and(|d| d % 2 == 1);
+
+    // whether to increase the magnitude of the kept digits
+    let increment = match mode {
+        RoundingMode::Truncate => false,
+        RoundingMode::Floor => negative && rest_nonzero,
+        RoundingMode::Ceil => !negative && rest_nonzero,
+        RoundingMode::HalfUp => half.is_gt() || (half.is_eq() && !negative),
+        RoundingMode::HalfAwayFromZero => half.is_ge(),
+        RoundingMode::HalfEven => half.is_gt() || (half.is_eq() && last_odd),
+    };
+    if increment {
+        let mut i = digits.len();
+        loop {
+            if i == 0 {
+                digits.insert(0, 1);
+                break;
+            }
+            i -= 1;
+            if digits[i] == 9 {
+                digits[i] = 0;
+            } else {
+                digits[i] += 1;
+                break;
+            }
+        }
+    }
+
+    let split = digits.len() - places;
+    let int_digits = digits[..split].iter().skip_while(|d| **d == 0).map(|d| (b'0' + d) as char);
+    let mut result: String = int_digits.collect();
+    if result.is_empty() {
+        result.push('0');
+    }
+    if places > 0 {
+        result.push('.');
+        result.extend(digits[split..].iter().map(|d| (b'0' + d) as char));
+    }
+    if negative && digits.iter().any(|d| *d != 0) {
+        result.insert(0, '-');
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_positive() {
+        assert_eq!(round(3.123456_f32, 2), 3.12);
+        assert_eq!(round(3.123456_f32, 4), 3.1235);
+        assert_eq!(round(3.999999_f32, 2), 4.0);
+        assert_eq!(round(3.0_f32, 2), 3.0);
+        assert_eq!(round(9.99999_f32, 2), 10.0);
+        assert_eq!(round(0_f32, 2), 0_f32);
+    }
+
+    #[test]
+    fn round_negative() {
+        assert_eq!(round(-3.123456_f32, 2), -3.12);
+        assert_eq!(round(-3.123456_f32, 4), -3.1235);
+        assert_eq!(round(-3.999999_f32, 2), -4.0);
+        assert_eq!(round(-3.0_f32, 2), -3.0);
+        assert_eq!(round(-9.999999_f32, 2), -10.0);
+    }
+
+    #[test]
+    fn round_f32_and_f64() {
+        assert_eq!(round(3.123456_f32, 4), 3.1235_f32);
+        assert_eq!(round(3.123456_f64, 4), 3.1235_f64);
+        assert_eq!(round(2.5_f32, 0), 3_f32);
+    }
+
+    #[test]
+    fn round_with_modes() {
+        use RoundingMode::*;
+
+        let cases = [
+            // value, HalfUp, HalfEven, HalfAwayFromZero, Floor, Ceil, Truncate
+            (2.5, [3.0, 2.0, 3.0, 2.0, 3.0, 2.0]),
+            (3.5, [4.0, 4.0, 4.0, 3.0, 4.0, 3.0]),
+            (-2.5, [-2.0, -2.0, -3.0, -3.0, -2.0, -2.0]),
+            (2.4, [2.0, 2.0, 2.0, 2.0, 3.0, 2.0]),
+            (-2.6, [-3.0, -3.0, -3.0, -3.0, -2.0, -2.0]),
+        ];
+        for (value, expected) in cases {
+            for (mode, want) in [HalfUp, HalfEven, HalfAwayFromZero, Floor, Ceil, Truncate]
+                .iter()
+                .zip(expected)
+            {
+                assert_eq!(round_with(value, 0, *mode), want, "{} with {:?}", value, mode);
+            }
+        }
+        assert_eq!(round_with(0.125, 2, HalfEven), 0.12);
+        assert_eq!(round_with(1.239, 2, Truncate), 1.23);
+    }
+
+    #[test]
+    fn round_many_places() {
+        assert_eq!(round(1.0 / 3.0, 15), 0.333333333333333);
+        assert_eq!(round(1.0 / 3.0, 20), 1.0 / 3.0);
+        assert_eq!(round(1.5_f32, 40), 1.5_f32);
+        assert_eq!(round(1e300, 400), 1e300);
+        assert_eq!(round(123_456.789_f64, usize::MAX), 123_456.789);
+    }
+
+    #[test]
+    fn round_exact_uses_decimal_digits() {
+        // 1.005 is stored as 1.00499999999999989341858963598497211933135986328125
+        assert_eq!(round_with(1.005, 2, RoundingMode::HalfUp), 1.0);
+        assert_eq!(round_exact(1.005, 2, RoundingMode::HalfUp), 1.01);
+        assert_eq!(round_exact(2.675, 2, RoundingMode::HalfUp), 2.68);
+        assert_eq!(round_exact(1.005_f32, 2, RoundingMode::HalfAwayFromZero), 1.01_f32);
+        assert_eq!(round_exact(2.665, 2, RoundingMode::HalfEven), 2.66);
+        assert!(round_exact(f64::NAN, 2, RoundingMode::HalfUp).is_nan());
+        assert_eq!(round_exact(f64::INFINITY, 2, RoundingMode::Floor), f64::INFINITY);
+    }
+
+    #[test]
+    fn round_exact_many_places() {
+        assert_eq!(round_exact(1.0 / 3.0, 100_000_000_000, RoundingMode::HalfUp), 1.0 / 3.0);
+        assert_eq!(round_exact(2.5_f32, usize::MAX, RoundingMode::Floor), 2.5_f32);
+        assert_eq!(round_exact(1e-300, 400, RoundingMode::Ceil), 1e-300);
+        assert_eq!(round_exact(1e-300, 299, RoundingMode::Ceil), 1e-299);
+        assert_eq!(round_exact(1e300, 100_000_000_000, RoundingMode::HalfEven), 1e300);
+    }
+
+    #[test]
+    fn round_decimal_strings() {
+        use RoundingMode::*;
+
+        assert_eq!(round_decimal("2.675", 2, HalfUp).unwrap(), "2.68");
+        assert_eq!(round_decimal("-2.675", 2, HalfUp).unwrap(), "-2.67");
+        assert_eq!(round_decimal("-2.675", 2, HalfAwayFromZero).unwrap(), "-2.68");
+        assert_eq!(round_decimal("2.665", 2, HalfEven).unwrap(), "2.66");
+        assert_eq!(round_decimal("2.6651", 2, HalfEven).unwrap(), "2.67");
+        assert_eq!(round_decimal("9.999", 2, HalfUp).unwrap(), "10.00");
+        assert_eq!(round_decimal("+1.2", 3, Floor).unwrap(), "1.200");
+        assert_eq!(round_decimal("-1.201", 2, Floor).unwrap(), "-1.21");
+        assert_eq!(round_decimal("1.201", 2, Ceil).unwrap(), "1.21");
+        assert_eq!(round_decimal("-0.004", 2, HalfUp).unwrap(), "0.00");
+        assert_eq!(round_decimal("0045.5", 0, HalfEven).unwrap(), "46");
+        assert_eq!(
+            round_decimal("3.14159265358979323846264338327950288", 30, HalfUp).unwrap(),
+            "3.141592653589793238462643383280"
+        );
+        assert!(round_decimal("1e5", 2, HalfUp).is_err());
+        assert!(round_decimal(".5", 2, HalfUp).is_err());
+        assert_eq!(
+            round_decimal("abc", 2, HalfUp).unwrap_err().to_string(),
+            "`abc` is not a decimal number like `-12.345`"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use crate::par_monte_carlo_pi;
+
+///
+/// An algorithm approximating pi, more closely with more iterations.
+///
+pub trait PiEstimator {
+    fn name(&self) -> &'static str;
+
+    ///
+    /// Approximates pi with `iterations` terms (or points, for Monte Carlo).
+    ///
+    fn estimate(&self, iterations: usize) -> f64;
+
+    ///
+    /// The absolute error of `estimate` against `std::f64::consts::PI`.
+    ///
+    fn error(&self, iterations: usize) -> f64 {
+        (self.estimate(iterations) - std::f64::consts::PI).abs()
+    }
+}
+
+///
+/// pi = 4 (1 - 1/3 + 1/5 - 1/7 + ...), which converges very slowly.
+///
+pub struct Leibniz;
+impl PiEstimator for Leibniz {
+    fn name(&self) -> &'static str {
+        "leibniz"
+    }
+
+    fn estimate(&self, iterations: usize) -> f64 {
+        let sum: f64 = (0..iterations)
+            .map(|k| {
+                let term = 1.0 / (2 * k + 1) as f64;
+                if k % 2 == 0 { term } else { -term }
+            })
+            .sum();
+        4.0 * sum
+    }
+}
+
+///
+/// pi = 3 + 4/(2*3*4) - 4/(4*5*6) + 4/(6*7*8) - ...
+///
+pub struct Nilakantha;
+impl PiEstimator for Nilakantha {
+    fn name(&self) -> &'static str {
+        "nilakantha"
+    }
+
+    fn estimate(&self, iterations: usize) -> f64 {
+        let sum: f64 = (1..=iterations)
+            .map(|k| {
+                let n = (2 * k) as f64;
+                let term = 4.0 / (n * (n + 1.0) * (n + 2.0));
+                if k % 2 == 1 { term } else { -term }
+            })
+            .sum();
+        3.0 + sum
+    }
+}
+
+///
+/// Machin's formula pi = 16 arctan(1/5) - 4 arctan(1/239), with both
+/// arctangents summed to `iterations` terms of their Taylor series.
+///
+pub struct Machin;
+impl Machin {
+    fn arctan_inverse(x: f64, terms: usize) -> f64 {
+        let x2 = x * x;
+        let mut power = 1.0 / x;
+        let mut sum = 0.0;
+        for k in 0..terms {
+            let term = power / (2 * k + 1) as f64;
+            sum += if k % 2 == 0 { term } else { -term };
+            power /= x2;
+        }
+        sum
+    }
+}
+impl PiEstimator for Machin {
+    fn name(&self) -> &'static str {
+        "machin"
+    }
+
+    fn estimate(&self, iterations: usize) -> f64 {
+        16.0 * Machin::arctan_inverse(5.0, iterations) - 4.0 * Machin::arctan_inverse(239.0, iterations)
+    }
+}
+
+///
+/// The Bailey–Borwein–Plouffe series, adding one hexadecimal digit per term.
+/// See `bbp_hex_digits` for extracting digits at an arbitrary position.
+///
+pub struct Bbp;
+impl PiEstimator for Bbp {
+    fn name(&self) -> &'static str {
+        "bbp"
+    }
+
+    fn estimate(&self, iterations: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut scale = 1.0;
+        for k in 0..iterations {
+            let k8 = (8 * k) as f64;
+            sum += scale
+                * (4.0 / (k8 + 1.0) - 2.0 / (k8 + 4.0) - 1.0 / (k8 + 5.0) - 1.0 / (k8 + 6.0));
+            scale /= 16.0;
+        }
+        sum
+    }
+}
+
+///
+/// The parallel Monte Carlo estimator, seeded for reproducible comparisons.
+/// Without points it estimates 0, like the series without terms.
+///
+pub struct MonteCarlo {
+    pub seed: u64,
+}
+impl PiEstimator for MonteCarlo {
+    fn name(&self) -> &'static str {
+        "monte-carlo"
+    }
+
+    fn estimate(&self, iterations: usize) -> f64 {
+        par_monte_carlo_pi(iterations, self.seed).map_or(0.0, |e| e.pi)
+    }
+}
+
+///
+/// Every estimator in this crate, Monte Carlo seeded with `seed`.
+///
+pub fn estimators(seed: u64) -> Vec<Box<dyn PiEstimator + Send + Sync>> {
+    vec![
+        Box::new(MonteCarlo { seed }),
+        Box::new(Leibniz),
+        Box::new(Nilakantha),
+        Box::new(Machin),
+        Box::new(Bbp),
+    ]
+}
+
+///
+/// Hex digits of pi per BBP evaluation. An `f64` carries about 13 hex
+/// digits, of which the last few are lost to rounding in the sums.
+///
+const DIGITS_PER_STEP: usize = 8;
+
+///
+/// Returns `count` hexadecimal digits of pi, starting `position` digits
+/// after the point, without computing the digits before them.
+///
+/// # Example
+///
+/// ```
+/// use rust_pilib::bbp_hex_digits;
+///
+/// // pi = 3.243F6A8885A308D3...
+/// assert_eq!(bbp_hex_digits(0, 8), "243F6A88");
+/// assert_eq!(bbp_hex_digits(8, 8), "85A308D3");
+/// ```
+///
+pub fn bbp_hex_digits(position: usize, count: usize) -> String {
+    let mut digits = String::with_capacity(count);
+    let mut at = position;
+    while digits.len() < count {
+        let mut frac = pi_fraction_at(at);
+        for _ in 0..DIGITS_PER_STEP.min(count - digits.len()) {
+            frac *= 16.0;
+            let digit = frac.floor();
+            digits.push(std::char::from_digit(digit as u32, 16).unwrap().to_ascii_uppercase());
+            frac -= digit;
+        }
+        at += DIGITS_PER_STEP;
+    }
+    digits
+}
+
+///
+/// The fractional part of 16^position * pi.
+///
+fn pi_fraction_at(position: usize) -> f64 {
+    let d = position as u64;
+    let s = 4.0 * bbp_series(1, d) - 2.0 * bbp_series(4, d) - bbp_series(5, d) - bbp_series(6, d);
+    fraction_below_one(s)
+}
+
+///
+/// `s - s.floor()`, which rounds to exactly 1.0 for tiny negative `s`; that
+/// is kept just below 1 so every hex digit drawn from it is at most 15.
+///
+fn fraction_below_one(s: f64) -> f64 {
+    (s - s.floor()).min(1.0 - f64::EPSILON / 2.0)
+}
+
+///
+/// The fractional part of sum_k 16^(d-k) / (8k+j), using modular
+/// exponentiation for the terms with k <= d so no precision is lost.
+///
+fn bbp_series(j: u64, d: u64) -> f64 {
+    let mut sum = 0.0;
+    for k in 0..=d {
+        let m = 8 * k + j;
+        sum += mod_pow(16, d - k, m) as f64 / m as f64;
+        sum -= sum.floor();
+    }
+
+    let mut scale = 1.0 / 16.0;
+    let mut k = d + 1;
+    while scale > 1e-17 {
+        sum += scale / (8 * k + j) as f64;
+        scale /= 16.0;
+        k += 1;
+    }
+    sum - sum.floor()
+}
+
+fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    let m = m as u128;
+    let mut result: u128 = 1;
+    let mut base = base as u128 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_series_converge() {
+        assert!(Leibniz.error(100_000) < 1e-4);
+        assert!(Nilakantha.error(1_000) < 1e-9);
+        assert!(Machin.error(12) < 1e-15);
+        assert!(Bbp.error(12) < 1e-14);
+        assert!(MonteCarlo { seed: 42 }.error(100_000) < 0.02);
+    }
+
+    #[test]
+    fn test_series_first_terms() {
+        assert_eq!(Leibniz.estimate(1), 4.0);
+        assert_eq!(Leibniz.estimate(2), 4.0 - 4.0 / 3.0);
+        assert_eq!(Nilakantha.estimate(0), 3.0);
+        assert_eq!(Nilakantha.estimate(1), 3.0 + 4.0 / 24.0);
+        assert_eq!(Bbp.estimate(1), 4.0 - 2.0 / 4.0 - 1.0 / 5.0 - 1.0 / 6.0);
+        assert_eq!(MonteCarlo { seed: 1 }.estimate(0), 0.0);
+    }
+
+    #[test]
+    fn test_estimators_are_ordered_by_convergence() {
+        let errors: Vec<f64> = [&Leibniz as &dyn PiEstimator, &Nilakantha, &Machin]
+            .iter()
+            .map(|e| e.error(10))
+            .collect();
+        assert!(errors.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(estimators(1).len(), 5);
+        assert!((Machin.estimate(20) - PI).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_bbp_hex_digits() {
+        // pi in hex: 3.243F6A8885A308D313198A2E03707344A4093822299F31D008...
+        let known = "243F6A8885A308D313198A2E03707344A4093822299F31D008";
+        assert_eq!(bbp_hex_digits(0, known.len()), known);
+        assert_eq!(bbp_hex_digits(13, 5), &known[13..18]);
+        assert_eq!(bbp_hex_digits(3, 0), "");
+        // the millionth hex digit of pi onwards, as published by Bailey et al.
+        // (who count the first digit after the point as position 1)
+        assert_eq!(bbp_hex_digits(999_999, 9), "26C65E52C");
+    }
+
+    #[test]
+    fn test_fraction_below_one() {
+        assert_eq!(fraction_below_one(2.25), 0.25);
+        assert_eq!(fraction_below_one(-0.25), 0.75);
+        assert!(fraction_below_one(-1e-20) < 1.0);
+        assert_eq!((fraction_below_one(-1e-20) * 16.0).floor(), 15.0);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(16, 0, 7), 1);
+        assert_eq!(mod_pow(16, 5, 7), 16_u64.pow(5) % 7);
+        assert_eq!(mod_pow(16, 10, 1), 0);
+    }
+}
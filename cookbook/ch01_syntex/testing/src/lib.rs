@@ -12,8 +12,14 @@
        test(no_crate_inject, attr(allow(unused_variables), deny(warnings))))]
 
 use std::cell::RefCell;
+use std::fmt;
+use std::iter::FromIterator;
 use std::rc::Rc;
 
+mod sync;
+
+pub use sync::SyncList;
+
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
 
 #[derive(Clone)]
@@ -24,7 +30,7 @@ struct Node<T> where T: Sized + Clone {
 impl<T> Node<T> where T: Sized + Clone {
     fn new(value: T) -> Rc<RefCell<Node<T>>> {
         Rc::new(RefCell::new(Node {
-            value: value,
+            value,
             next: None,
         }))
     }
@@ -44,7 +50,6 @@ impl<T> Node<T> where T: Sized + Clone {
 /// let list = List::new_empty();
 /// ```
 /// 
-#[derive(Clone)]
 pub struct List<T> where T: Sized + Clone {
     head: Link<T>,
     tail: Link<T>,
@@ -67,6 +72,37 @@ impl<T> List<T> where T: Sized + Clone {
         List { head: None, tail: None, length: 0 }
     }
     ///
+    /// Returns the number of nodes in the list.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// # use testing::List;
+    /// let list: List<i32> = vec![1, 2].into_iter().collect();
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    /// 
+    pub fn len(&self) -> usize {
+        self.length
+    }
+    ///
+    /// Returns `true` if the list has no nodes.
+    /// 
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+    ///
+    /// Calls `f` with every value, from head to tail.
+    /// 
+    fn for_each_value(&self, mut f: impl FnMut(&T)) {
+        let mut current = self.head.clone();
+        while let Some(node) = current {
+            let node = node.borrow();
+            f(&node.value);
+            current = node.next.clone();
+        }
+    }
+    ///
     /// Appends a node to the list at the end.
     /// 
     ///  
@@ -152,6 +188,77 @@ impl<T> List<T> where T: Sized + Clone {
         })
     } 
 }
+impl<T> Drop for List<T> where T: Sized + Clone {
+    ///
+    /// Unlinks the nodes one by one. The default drop would recurse
+    /// through every `next` link and overflow the stack on long lists.
+    /// 
+    fn drop(&mut self) {
+        self.tail.take();
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            current = node.borrow_mut().next.take();
+        }
+    }
+}
+impl<T> Clone for List<T> where T: Sized + Clone {
+    ///
+    /// Copies every node, so the clone doesn't share nodes with the original.
+    /// 
+    fn clone(&self) -> Self {
+        let mut list = List::new_empty();
+        self.for_each_value(|value| list.append(value.clone()));
+        list
+    }
+}
+impl<T> Default for List<T> where T: Sized + Clone {
+    fn default() -> Self {
+        List::new_empty()
+    }
+}
+impl<T> fmt::Debug for List<T> where T: Sized + Clone + fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        self.for_each_value(|value| {
+            list.entry(value);
+        });
+        list.finish()
+    }
+}
+impl<T> PartialEq for List<T> where T: Sized + Clone + PartialEq {
+    fn eq(&self, other: &Self) -> bool {
+        if self.length != other.length {
+            return false;
+        }
+
+        let mut left = self.head.clone();
+        let mut right = other.head.clone();
+        while let (Some(l), Some(r)) = (left, right) {
+            let (l, r) = (l.borrow(), r.borrow());
+            if l.value != r.value {
+                return false;
+            }
+            left = l.next.clone();
+            right = r.next.clone();
+        }
+        true
+    }
+}
+impl<T> Eq for List<T> where T: Sized + Clone + Eq {}
+impl<T> Extend<T> for List<T> where T: Sized + Clone {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.append(value);
+        }
+    }
+}
+impl<T> FromIterator<T> for List<T> where T: Sized + Clone {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new_empty();
+        list.extend(iter);
+        list
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -208,4 +315,33 @@ mod test {
         assert_eq!(list.length, 0);
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn test_list_collection_traits() {
+        let mut list: List<i32> = (1..=3).collect();
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+
+        list.extend(vec![4, 5]);
+        assert_eq!(list, (1..=5).collect());
+        assert_ne!(list, (1..=4).collect());
+        assert_ne!(list, (2..=6).collect());
+
+        let empty: List<i32> = List::default();
+        assert!(empty.is_empty());
+        assert_eq!(format!("{:?}", empty), "[]");
+    }
+
+    #[test]
+    fn test_list_clone_is_independent() {
+        let mut list: List<i32> = (1..=3).collect();
+        let copy = list.clone();
+        list.append(4);
+        assert_eq!(list.pop(), Some(1));
+        drop(list);
+
+        assert_eq!(copy.len(), 3);
+        assert_eq!(copy, vec![1, 2, 3].into_iter().collect());
+    }
 }
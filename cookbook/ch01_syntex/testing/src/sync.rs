@@ -0,0 +1,147 @@
+//!
+//! A thread-safe counterpart to `List`, using `Arc`s and `Mutex`es where
+//! `List` uses `Rc`s and `RefCell`s.
+//!
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+type Link<T> = Arc<Mutex<Node<T>>>;
+
+struct Node<T> {
+    // `None` only for the node in front of the head (see `SyncList`)
+    value: Option<T>,
+    next: Option<Link<T>>,
+}
+impl<T> Node<T> {
+    fn new(value: Option<T>) -> Link<T> {
+        Arc::new(Mutex::new(Node { value, next: None }))
+    }
+}
+
+///
+/// A singly-linked list that can be shared between threads, e.g. as a
+/// queue between producers calling `append` and consumers calling `pop`.
+///
+/// The list always starts with an empty "dummy" node, so `head` and
+/// `tail` are guarded by separate locks: appending only locks the tail
+/// and popping only locks the head, which lets producers and consumers
+/// work at the same time.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use testing::SyncList;
+///
+/// let list = Arc::new(SyncList::new_empty());
+/// let producer = {
+///     let list = Arc::clone(&list);
+///     thread::spawn(move || list.append(10))
+/// };
+/// producer.join().unwrap();
+///
+/// assert_eq!(list.pop(), Some(10));
+/// ```
+///
+pub struct SyncList<T> {
+    head: Mutex<Link<T>>,
+    tail: Mutex<Link<T>>,
+    length: AtomicUsize,
+}
+impl<T> SyncList<T> {
+    ///
+    /// Creates a new empty list.
+    ///
+    pub fn new_empty() -> SyncList<T> {
+        let dummy = Node::new(None);
+        SyncList {
+            head: Mutex::new(Arc::clone(&dummy)),
+            tail: Mutex::new(dummy),
+            length: AtomicUsize::new(0),
+        }
+    }
+
+    ///
+    /// Appends a node to the list at the end.
+    ///
+    pub fn append(&self, value: T) {
+        let new = Node::new(Some(value));
+        let mut tail = self.tail.lock().expect("tail lock poisoned");
+        // counted before the node can be popped, so `pop` never takes the
+        // length below zero
+        self.length.fetch_add(1, Ordering::SeqCst);
+        tail.lock().expect("node lock poisoned").next = Some(Arc::clone(&new));
+        *tail = new;
+    }
+
+    ///
+    /// Removes the list's head and returns the result.
+    ///
+    pub fn pop(&self) -> Option<T> {
+        let mut head = self.head.lock().expect("head lock poisoned");
+        let next = head.lock().expect("node lock poisoned").next.clone()?;
+        let value = next.lock().expect("node lock poisoned").value.take();
+        // the popped node becomes the new dummy
+        *head = next;
+        self.length.fetch_sub(1, Ordering::SeqCst);
+        value
+    }
+
+    ///
+    /// The number of nodes. While other threads modify the list this is
+    /// only a snapshot.
+    ///
+    pub fn len(&self) -> usize {
+        self.length.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+impl<T> Default for SyncList<T> {
+    fn default() -> Self {
+        SyncList::new_empty()
+    }
+}
+impl<T> Drop for SyncList<T> {
+    ///
+    /// Unlinks the nodes one by one to avoid recursing through long lists.
+    ///
+    fn drop(&mut self) {
+        let head = self.head.get_mut().expect("head lock poisoned");
+        let mut current = head.lock().expect("node lock poisoned").next.take();
+        while let Some(node) = current {
+            current = node.lock().expect("node lock poisoned").next.take();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_list_append_and_pop() {
+        let list = SyncList::new_empty();
+        assert_eq!(list.pop(), None);
+
+        list.append(1);
+        list.append(2);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.pop(), Some(1));
+        list.append(3);
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_sync_list_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SyncList<String>>();
+    }
+}
@@ -7,4 +7,25 @@ fn test_list_insert_10k_item() {
         list.append(100);
     }
     assert_eq!(list.length, 1000);
-}
\ No newline at end of file
+}
+#[test]
+fn test_list_drop_1m_items() {
+    let list: List<usize> = (0..1_000_000).collect();
+    assert_eq!(list.len(), 1_000_000);
+    drop(list);
+}
+
+#[test]
+fn test_list_used_like_vec() {
+    let v: Vec<i32> = (0..100).collect();
+    let list: List<i32> = v.iter().cloned().collect();
+    let mut copy = list.clone();
+    let mut popped = vec![];
+    while let Some(value) = copy.pop() {
+        popped.push(value);
+    }
+
+    assert_eq!(popped, v);
+    assert!(copy.is_empty());
+    assert_eq!(list.len(), v.len());
+}
@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use testing::SyncList;
+
+const PRODUCERS: usize = 4;
+const CONSUMERS: usize = 4;
+const ITEMS: usize = 20_000;
+
+#[test]
+fn test_sync_list_no_items_lost_or_duplicated() {
+    let list = Arc::new(SyncList::new_empty());
+    let popped = Arc::new(AtomicUsize::new(0));
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let list = Arc::clone(&list);
+            thread::spawn(move || {
+                for i in 0..ITEMS {
+                    list.append((p, i));
+                }
+            })
+        })
+        .collect();
+
+    let consumers: Vec<_> = (0..CONSUMERS)
+        .map(|_| {
+            let list = Arc::clone(&list);
+            let popped = Arc::clone(&popped);
+            thread::spawn(move || {
+                let mut seen = vec![];
+                while popped.load(Ordering::SeqCst) < PRODUCERS * ITEMS {
+                    match list.pop() {
+                        Some(item) => {
+                            popped.fetch_add(1, Ordering::SeqCst);
+                            seen.push(item);
+                        }
+                        None => thread::yield_now(),
+                    }
+                    // a length that wrapped below zero would be huge
+                    assert!(list.len() <= PRODUCERS * ITEMS);
+                }
+                seen
+            })
+        })
+        .collect();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let seen: Vec<Vec<(usize, usize)>> = consumers.into_iter().map(|c| c.join().unwrap()).collect();
+
+    // each consumer sees every producer's items in the order they were appended
+    for items in &seen {
+        for p in 0..PRODUCERS {
+            let order: Vec<usize> = items.iter().filter(|(q, _)| *q == p).map(|(_, i)| *i).collect();
+            assert!(order.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    let mut all: Vec<(usize, usize)> = seen.into_iter().flatten().collect();
+    all.sort();
+    let expected: Vec<(usize, usize)> = (0..PRODUCERS)
+        .flat_map(|p| (0..ITEMS).map(move |i| (p, i)))
+        .collect();
+    assert_eq!(all, expected);
+    assert!(list.is_empty());
+    assert_eq!(list.pop(), None);
+}
+
+#[test]
+fn test_sync_list_concurrent_appends() {
+    let list = Arc::new(SyncList::new_empty());
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            let list = Arc::clone(&list);
+            thread::spawn(move || {
+                for i in 0..10_000 {
+                    list.append(t * 10_000 + i);
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    assert_eq!(list.len(), 80_000);
+    let mut all = vec![];
+    while let Some(v) = list.pop() {
+        all.push(v);
+    }
+    all.sort();
+    assert_eq!(all, (0..80_000).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_sync_list_drop_long_list() {
+    let list = SyncList::new_empty();
+    for i in 0..1_000_000 {
+        list.append(i);
+    }
+    drop(list);
+}
@@ -0,0 +1,347 @@
+use std::io::{Error, ErrorKind, Read, Write};
+
+use serde_json::{Map, Number, Value};
+
+use crate::{Config, ConfigReader, ConfigWriter};
+
+///
+/// Reads a config with one format and writes it with another.
+///
+/// # Example
+///
+/// ```
+/// # use traits::{convert, JsonConfigService, TomlConfigService};
+/// let json = r#"{ "server": { "port": 80 } }"#;
+/// let mut toml = vec![];
+/// convert(&JsonConfigService::new(), &mut json.as_bytes(), &TomlConfigService::new(), &mut toml).unwrap();
+///
+/// assert_eq!(String::from_utf8(toml).unwrap(), "[server]\nport = 80\n");
+/// ```
+///
+pub fn convert(
+    reader: &impl ConfigReader,
+    from: &mut impl Read,
+    writer: &impl ConfigWriter,
+    to: &mut impl Write,
+) -> std::io::Result<()> {
+    let config = reader.read(from)?;
+    writer.write(config, to)
+}
+
+fn invalid_data(e: impl std::fmt::Display) -> Error {
+    Error::new(ErrorKind::InvalidData, e.to_string())
+}
+
+fn invalid_input(e: impl std::fmt::Display) -> Error {
+    Error::new(ErrorKind::InvalidInput, e.to_string())
+}
+
+///
+/// Flattens a document tree into dotted keys. Arrays use their indices
+/// as keys (`hosts.0`, `hosts.1`) and nulls are skipped.
+///
+fn flatten(tree: Value) -> std::io::Result<Config> {
+    let map = match tree {
+        Value::Object(map) => map,
+        other => return Err(invalid_data(format!("expected a table at the top level, found `{}`", other))),
+    };
+
+    let mut values = vec![];
+    flatten_into(&mut values, None, Value::Object(map));
+    Ok(Config::new(values))
+}
+
+fn flatten_into(values: &mut Vec<(String, String)>, prefix: Option<&str>, value: Value) {
+    let child = |key: &str| match prefix {
+        Some(p) => format!("{}.{}", p, key),
+        None => key.to_string(),
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                flatten_into(values, Some(&child(&k)), v);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.into_iter().enumerate() {
+                flatten_into(values, Some(&child(&i.to_string())), v);
+            }
+        }
+        Value::Null => {}
+        Value::String(s) => values.push((prefix.unwrap_or_default().to_string(), s)),
+        other => values.push((prefix.unwrap_or_default().to_string(), other.to_string())),
+    }
+}
+
+///
+/// Rebuilds a document tree from dotted keys. Tables whose keys are
+/// exactly `0..n` become arrays, and strings become booleans or numbers
+/// when that renders back to the very same text, so the round trip
+/// through a `Config` is lossless.
+///
+fn unflatten(config: &Config) -> std::io::Result<Value> {
+    let mut root = Map::new();
+    for (key, value) in &config.values {
+        let parts: Vec<&str> = key.split('.').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return Err(invalid_input(format!("key `{}` has an empty segment", key)));
+        }
+
+        let (last, tables) = parts.split_last().expect("split yields at least one part");
+        let mut current = &mut root;
+        for (depth, part) in tables.iter().enumerate() {
+            let entry = current
+                .entry(part.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            current = match entry {
+                Value::Object(map) => map,
+                _ => {
+                    return Err(invalid_input(format!(
+                        "key `{}` is both a value and a table",
+                        parts[..=depth].join(".")
+                    )))
+                }
+            };
+        }
+        if current.contains_key(*last) {
+            return Err(invalid_input(format!("key `{}` is both a value and a table", key)));
+        }
+        current.insert(last.to_string(), scalar(value));
+    }
+    Ok(arrays(Value::Object(root)))
+}
+
+fn scalar(value: &str) -> Value {
+    if let Ok(b) = value.parse::<bool>() {
+        return Value::Bool(b);
+    }
+    if let Ok(i) = value.parse::<i64>() {
+        if i.to_string() == value {
+            return Value::Number(i.into());
+        }
+    }
+    if let Some(n) = value.parse::<f64>().ok().and_then(Number::from_f64) {
+        if n.to_string() == value {
+            return Value::Number(n);
+        }
+    }
+    Value::String(value.to_string())
+}
+
+fn arrays(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let is_array = !map.is_empty()
+                && map.keys().enumerate().all(|(i, k)| *k == i.to_string());
+            let items = map.into_iter().map(|(k, v)| (k, arrays(v)));
+            if is_array {
+                Value::Array(items.map(|(_, v)| v).collect())
+            } else {
+                Value::Object(items.collect())
+            }
+        }
+        other => other,
+    }
+}
+
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Number::from_f64(f)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(f.to_string())),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => {
+            Value::Object(table.into_iter().map(|(k, v)| (k, from_toml(v))).collect())
+        }
+    }
+}
+
+///
+/// Reads and writes configs as JSON, mapping nested objects to dotted keys.
+///
+#[derive(Default)]
+pub struct JsonConfigService {}
+impl JsonConfigService {
+    pub fn new() -> JsonConfigService {
+        JsonConfigService {}
+    }
+}
+impl ConfigReader for JsonConfigService {
+    fn read(&self, from: &mut impl Read) -> std::io::Result<Config> {
+        flatten(serde_json::from_reader(from).map_err(invalid_data)?)
+    }
+}
+impl ConfigWriter for JsonConfigService {
+    fn write(&self, config: Config, mut to: &mut impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut to, &unflatten(&config)?)?;
+        writeln!(&mut to)
+    }
+}
+
+///
+/// Reads and writes configs as TOML, mapping tables to dotted keys.
+/// Dates and times are read as strings.
+///
+#[derive(Default)]
+pub struct TomlConfigService {}
+impl TomlConfigService {
+    pub fn new() -> TomlConfigService {
+        TomlConfigService {}
+    }
+}
+impl ConfigReader for TomlConfigService {
+    fn read(&self, from: &mut impl Read) -> std::io::Result<Config> {
+        let mut buffer = String::new();
+        from.read_to_string(&mut buffer)?;
+
+        let table: toml::Table = buffer.parse().map_err(invalid_data)?;
+        flatten(from_toml(toml::Value::Table(table)))
+    }
+}
+impl ConfigWriter for TomlConfigService {
+    fn write(&self, config: Config, to: &mut impl Write) -> std::io::Result<()> {
+        let text = toml::to_string(&unflatten(&config)?).map_err(invalid_input)?;
+        to.write_all(text.as_bytes())
+    }
+}
+
+///
+/// Reads and writes configs as YAML, mapping nested mappings to dotted keys.
+/// Mapping keys must be strings.
+///
+#[derive(Default)]
+pub struct YamlConfigService {}
+impl YamlConfigService {
+    pub fn new() -> YamlConfigService {
+        YamlConfigService {}
+    }
+}
+impl ConfigReader for YamlConfigService {
+    fn read(&self, from: &mut impl Read) -> std::io::Result<Config> {
+        flatten(serde_yaml::from_reader(from).map_err(invalid_data)?)
+    }
+}
+impl ConfigWriter for YamlConfigService {
+    fn write(&self, config: Config, to: &mut impl Write) -> std::io::Result<()> {
+        serde_yaml::to_writer(to, &unflatten(&config)?).map_err(invalid_input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyValueConfigService, ValueGetter};
+
+    fn sample() -> Config {
+        Config::new(
+            [
+                ("name", "app"),
+                ("debug", "true"),
+                ("server.port", "8080"),
+                ("server.ratio", "0.5"),
+                ("server.zip", "007"),
+                ("server.hosts.0", "a"),
+                ("server.hosts.1", "b"),
+                ("db.users.0.name", "root"),
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        )
+    }
+
+    fn round_trip(service: &(impl ConfigReader + ConfigWriter)) -> (String, Config) {
+        let mut target = vec![];
+        service.write(sample(), &mut target).expect("Couldn't write");
+        let config = service.read(&mut target.as_slice()).expect("Couldn't read");
+        (String::from_utf8(target).unwrap(), config)
+    }
+
+    #[test]
+    fn json_config_service_round_trip() {
+        let (text, config) = round_trip(&JsonConfigService::new());
+
+        assert_eq!(config, sample());
+        assert!(text.contains("\"port\": 8080"));
+        assert!(text.contains("\"zip\": \"007\""));
+        assert!(text.contains("\"hosts\": [\n"));
+    }
+
+    #[test]
+    fn toml_config_service_round_trip() {
+        let (text, config) = round_trip(&TomlConfigService::new());
+
+        assert_eq!(config, sample());
+        assert!(text.contains("[server]\nport = 8080\n"));
+    }
+
+    #[test]
+    fn yaml_config_service_round_trip() {
+        let (text, config) = round_trip(&YamlConfigService::new());
+
+        assert_eq!(config, sample());
+        assert!(text.contains("  port: 8080\n"));
+    }
+
+    #[test]
+    fn formats_read_nested_tables_as_dotted_keys() {
+        let yaml = "server:\n  port: 80\n  tls: null\n  hosts: [a, b]\nname: app\n";
+        let config = YamlConfigService::new().read(&mut yaml.as_bytes()).unwrap();
+
+        assert_eq!(config.get("server.port"), Some("80".to_string()));
+        assert_eq!(config.get("server.tls"), None);
+        assert_eq!(config.get("server.hosts.1"), Some("b".to_string()));
+        assert_eq!(config.get_list("server.hosts"), Ok(vec!["a".to_string(), "b".to_string()]));
+
+        let toml = "when = 1979-05-27T07:32:00Z\n[server]\nport = 80\n";
+        let config = TomlConfigService::new().read(&mut toml.as_bytes()).unwrap();
+        assert_eq!(config.get("when"), Some("1979-05-27T07:32:00Z".to_string()));
+    }
+
+    #[test]
+    fn formats_convert_between_each_other() {
+        let ini = "name=app\n[server]\nport=80\n";
+        let mut json = vec![];
+        convert(
+            &KeyValueConfigService::new(),
+            &mut ini.as_bytes(),
+            &JsonConfigService::new(),
+            &mut json,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(json.clone()).unwrap(),
+            "{\n  \"name\": \"app\",\n  \"server\": {\n    \"port\": 80\n  }\n}\n"
+        );
+
+        let mut back = vec![];
+        convert(
+            &JsonConfigService::new(),
+            &mut json.as_slice(),
+            &KeyValueConfigService::new(),
+            &mut back,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(back).unwrap(), ini);
+    }
+
+    #[test]
+    fn formats_reject_conflicting_keys_and_bad_input() {
+        let config = Config::new(vec![
+            ("a".to_string(), "1".to_string()),
+            ("a.b".to_string(), "2".to_string()),
+        ]);
+        let err = JsonConfigService::new().write(config, &mut vec![]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "key `a` is both a value and a table");
+
+        let err = JsonConfigService::new().read(&mut "[1, 2]".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+
+use crate::{Config, ConfigReader, ValueGetter};
+
+///
+/// Where a layer's values come from, in increasing order of precedence.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    Default,
+    File,
+    Environment,
+    Override,
+}
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Source::Default => "default",
+            Source::File => "file",
+            Source::Environment => "environment",
+            Source::Override => "override",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+///
+/// A value as found in one layer, together with the layer it came from.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Origin {
+    pub source: Source,
+    pub name: String,
+    pub value: String,
+}
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` from {} `{}`", self.value, self.source, self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    source: Source,
+    name: String,
+    config: Config,
+}
+
+///
+/// Several configs stacked by precedence:
+/// defaults < files < environment < overrides.
+///
+/// Within the same `Source`, a layer added later wins over earlier ones,
+/// so a local file can be stacked on top of a shared one.
+///
+/// # Example
+///
+/// ```
+/// # use traits::{Config, LayeredConfig, Source, ValueGetter};
+/// let file = Config::new(vec![("server.port".to_string(), "80".to_string())]);
+/// let config = LayeredConfig::new()
+///     .with_file("app.conf", file)
+///     .with_env_vars("APP_", vec![("APP_SERVER__PORT".to_string(), "8080".to_string())]);
+///
+/// assert_eq!(config.get("server.port"), Some("8080".to_string()));
+/// assert_eq!(config.source_of("server.port"), Some(Source::Environment));
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct LayeredConfig {
+    layers: Vec<Layer>,
+}
+impl LayeredConfig {
+    pub fn new() -> LayeredConfig {
+        LayeredConfig { layers: vec![] }
+    }
+
+    ///
+    /// Adds a layer, keeping the layers sorted by precedence.
+    ///
+    pub fn with_layer(mut self, source: Source, name: &str, config: Config) -> LayeredConfig {
+        let pos = self.layers.partition_point(|l| l.source <= source);
+        self.layers.insert(
+            pos,
+            Layer { source, name: name.to_string(), config },
+        );
+        self
+    }
+
+    pub fn with_defaults(self, defaults: Vec<(String, String)>) -> LayeredConfig {
+        self.with_layer(Source::Default, "defaults", Config::new(defaults))
+    }
+
+    pub fn with_file(self, name: &str, config: Config) -> LayeredConfig {
+        self.with_layer(Source::File, name, config)
+    }
+
+    ///
+    /// Reads the file at `path` with `reader` and adds it as a file layer.
+    ///
+    pub fn read_file(
+        self,
+        reader: &impl ConfigReader,
+        path: impl AsRef<Path>,
+    ) -> std::io::Result<LayeredConfig> {
+        let path = path.as_ref();
+        let config = reader.read(&mut File::open(path)?)?;
+        Ok(self.with_file(&path.display().to_string(), config))
+    }
+
+    ///
+    /// Adds the process environment variables starting with `prefix`.
+    ///
+    pub fn with_env(self, prefix: &str) -> LayeredConfig {
+        self.with_env_vars(prefix, std::env::vars())
+    }
+
+    ///
+    /// Adds the variables starting with `prefix`, mapping their names to
+    /// keys: the prefix is removed, the rest lowercased and `__` becomes
+    /// the section separator, so `APP_SERVER__MAX_CONN` is `server.max_conn`.
+    ///
+    pub fn with_env_vars(
+        self,
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> LayeredConfig {
+        let values = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                name.strip_prefix(prefix)
+                    .filter(|rest| !rest.is_empty())
+                    .map(|rest| (rest.to_lowercase().replace("__", "."), value))
+            })
+            .collect();
+        self.with_layer(Source::Environment, &format!("{}*", prefix), Config::new(values))
+    }
+
+    pub fn with_overrides(self, overrides: Vec<(String, String)>) -> LayeredConfig {
+        self.with_layer(Source::Override, "overrides", Config::new(overrides))
+    }
+
+    ///
+    /// Returns the value for `key` together with the layer that supplied it.
+    ///
+    pub fn origin(&self, key: &str) -> Option<Origin> {
+        self.origins(key).next()
+    }
+
+    pub fn source_of(&self, key: &str) -> Option<Source> {
+        self.origin(key).map(|o| o.source)
+    }
+
+    ///
+    /// Lists every layer defining `key`, highest precedence first, which
+    /// shows what the winning value has shadowed.
+    ///
+    /// Defaults declared on a layer's `Config` (see `Config::with_defaults`)
+    /// rank below every value actually set in any layer, and are reported
+    /// as coming from `Source::Default`.
+    ///
+    pub fn trace(&self, key: &str) -> Vec<Origin> {
+        self.origins(key).collect()
+    }
+
+    fn origins<'a>(&'a self, key: &'a str) -> impl Iterator<Item = Origin> + 'a {
+        let values = self.layers.iter().rev().filter_map(move |layer| {
+            layer.config.get_value(key).map(|value| Origin {
+                source: layer.source,
+                name: layer.name.clone(),
+                value,
+            })
+        });
+        let defaults = self.layers.iter().rev().filter_map(move |layer| {
+            layer.config.get_default(key).map(|value| Origin {
+                source: Source::Default,
+                name: layer.name.clone(),
+                value,
+            })
+        });
+        values.chain(defaults)
+    }
+
+    ///
+    /// Flattens all layers into a single `Config` holding the winning
+    /// value of every key, so its typed getters can be used.
+    ///
+    pub fn resolve(&self) -> Config {
+        let mut keys: Vec<&str> = vec![];
+        for layer in &self.layers {
+            for (k, _) in layer.config.values.iter().chain(layer.config.defaults.iter()) {
+                if !keys.contains(&k.as_str()) {
+                    keys.push(k);
+                }
+            }
+        }
+
+        Config::new(
+            keys.into_iter()
+                .filter_map(|k| self.get(k).map(|v| (k.to_string(), v)))
+                .collect(),
+        )
+    }
+}
+impl ValueGetter for LayeredConfig {
+    fn get(&self, s: &str) -> Option<String> {
+        self.origin(s).map(|o| o.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn layered() -> LayeredConfig {
+        // added out of order on purpose, precedence must not depend on it
+        LayeredConfig::new()
+            .with_overrides(pairs(&[("log", "trace")]))
+            .with_env_vars(
+                "APP_",
+                pairs(&[("APP_SERVER__PORT", "9000"), ("APP_LOG", "debug"), ("OTHER", "x")]),
+            )
+            .with_file("shared.conf", Config::new(pairs(&[("server.port", "80"), ("server.host", "a")])))
+            .with_file("local.conf", Config::new(pairs(&[("server.host", "b")])))
+            .with_defaults(pairs(&[("server.host", "localhost"), ("timeout", "30s"), ("log", "info")]))
+    }
+
+    #[test]
+    fn layered_config_resolves_by_precedence() {
+        let config = layered();
+
+        assert_eq!(config.get("timeout"), Some("30s".to_string()));
+        assert_eq!(config.get("server.host"), Some("b".to_string()));
+        assert_eq!(config.get("server.port"), Some("9000".to_string()));
+        assert_eq!(config.get("log"), Some("trace".to_string()));
+        assert_eq!(config.get("other"), None);
+    }
+
+    #[test]
+    fn layered_config_reports_origin() {
+        let config = layered();
+
+        assert_eq!(config.source_of("timeout"), Some(Source::Default));
+        assert_eq!(config.source_of("server.port"), Some(Source::Environment));
+        assert_eq!(config.source_of("missing"), None);
+        assert_eq!(
+            config.origin("server.host").unwrap().to_string(),
+            "`b` from file `local.conf`"
+        );
+        assert_eq!(
+            config
+                .trace("log")
+                .iter()
+                .map(|o| (o.source, o.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Source::Override, "trace"),
+                (Source::Environment, "debug"),
+                (Source::Default, "info")
+            ]
+        );
+    }
+
+    #[test]
+    fn layered_config_declared_defaults_rank_lowest() {
+        let config = LayeredConfig::new()
+            .with_defaults(pairs(&[("log", "info")]))
+            .with_file("shared.conf", Config::new(pairs(&[("server.host", "a")])))
+            .with_file(
+                "local.conf",
+                Config::new(vec![]).with_defaults(pairs(&[("server.host", "z"), ("log", "warn"), ("retries", "3")])),
+            );
+
+        assert_eq!(config.get("server.host"), Some("a".to_string()));
+        assert_eq!(config.source_of("server.host"), Some(Source::File));
+        assert_eq!(config.get("log"), Some("info".to_string()));
+        assert_eq!(config.get("retries"), Some("3".to_string()));
+        assert_eq!(
+            config.origin("retries").unwrap().to_string(),
+            "`3` from default `local.conf`"
+        );
+        assert_eq!(
+            config
+                .trace("server.host")
+                .iter()
+                .map(|o| (o.source, o.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(Source::File, "a"), (Source::Default, "z")]
+        );
+        assert_eq!(config.resolve().get("server.host"), Some("a".to_string()));
+    }
+
+    #[test]
+    fn layered_config_resolve_flattens() {
+        let config = layered().resolve();
+
+        assert_eq!(config.get_u64("server.port"), Ok(9000));
+        assert_eq!(config.get("server.host"), Some("b".to_string()));
+        assert_eq!(config.get("log"), Some("trace".to_string()));
+    }
+}
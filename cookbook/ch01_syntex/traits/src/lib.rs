@@ -1,5 +1,19 @@
+use std::error::Error;
+use std::fmt;
 use std::io::{Read, Write};
 
+mod formats;
+mod layered;
+mod schema;
+mod typed;
+mod watcher;
+
+pub use formats::{convert, JsonConfigService, TomlConfigService, YamlConfigService};
+pub use layered::{LayeredConfig, Origin, Source};
+pub use schema::{Field, Schema, ValidationErrors, ValueType, Violation};
+pub use typed::ValueError;
+pub use watcher::{ConfigChange, ConfigWatcher, WatchHandle};
+
 /* TRAIT */
 ///
 /// Provides a get() function to return values associated with
@@ -22,41 +36,354 @@ pub trait ConfigReader {
 }
 
 /* STRUCT */
+///
+/// A single line of a config document, kept so that writing a
+/// config reproduces comments, sections and ordering.
+///
+#[derive(Debug, Clone)]
+enum Line {
+    Blank,
+    Comment(String),
+    Section(String),
+    Entry(usize),
+}
+
 ///
 /// Configuration for our application
 ///
+/// Keys inside a `[section]` are stored qualified as `section.key`.
+/// Defaults are consulted by `get` when a key has no value, but are
+/// never written out.
+///
+#[derive(Debug, Clone)]
 pub struct Config {
-    values: Vec<(String, String)>
+    values: Vec<(String, String)>,
+    layout: Vec<Line>,
+    defaults: Vec<(String, String)>,
 }
 impl Config {
     pub fn new(values: Vec<(String, String)>) -> Config {
-        Config { values: values }
+        let layout = Config::default_layout(&values);
+        Config { values, layout, defaults: vec![] }
+    }
+
+    ///
+    /// Declares fallback values for keys missing from this config.
+    /// Later defaults for the same key replace earlier ones.
+    ///
+    pub fn with_defaults(mut self, defaults: Vec<(String, String)>) -> Config {
+        for (key, value) in defaults {
+            self.set_default(&key, &value);
+        }
+        self
+    }
+
+    ///
+    /// Declares a single fallback value for `key`.
+    ///
+    pub fn set_default(&mut self, key: &str, value: &str) {
+        match self.defaults.iter_mut().find(|(k, _)| k == key) {
+            Some(default) => default.1 = value.to_string(),
+            None => self.defaults.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    ///
+    /// The value set for `key`, ignoring declared defaults.
+    ///
+    pub(crate) fn get_value(&self, key: &str) -> Option<String> {
+        find_value(&self.values, key)
+    }
+
+    ///
+    /// The default declared for `key`, ignoring any value set for it.
+    ///
+    pub(crate) fn get_default(&self, key: &str) -> Option<String> {
+        find_value(&self.defaults, key)
+    }
+
+    ///
+    /// Groups keys without a section first, followed by one
+    /// `[section]` per distinct prefix in order of appearance.
+    ///
+    fn default_layout(values: &[(String, String)]) -> Vec<Line> {
+        let mut layout: Vec<Line> = values
+            .iter()
+            .enumerate()
+            .filter(|(_, (k, _))| split_key(k).0.is_none())
+            .map(|(i, _)| Line::Entry(i))
+            .collect();
+
+        let mut sections: Vec<&str> = vec![];
+        for (k, _) in values {
+            if let (Some(section), _) = split_key(k) {
+                if !sections.contains(&section) {
+                    sections.push(section);
+                }
+            }
+        }
+        for section in sections {
+            layout.push(Line::Section(section.to_string()));
+            layout.extend(
+                values
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (k, _))| split_key(k).0 == Some(section))
+                    .map(|(i, _)| Line::Entry(i)),
+            );
+        }
+        layout
+    }
+}
+impl PartialEq for Config {
+    fn eq(&self, other: &Config) -> bool {
+        self.values == other.values
     }
 }
 impl ValueGetter for Config {
     fn get(&self, s: &str) -> Option<String> {
-        self.values.iter().find_map(|tuple| {
-            if &tuple.0 == s {
-                Some(tuple.1.clone())
-            } else {
-                None
+        self.get_value(s).or_else(|| self.get_default(s))
+    }
+}
+
+fn find_value(values: &[(String, String)], key: &str) -> Option<String> {
+    values.iter().find_map(|tuple| {
+        if tuple.0 == key {
+            Some(tuple.1.clone())
+        } else {
+            None
+        }
+    })
+}
+
+///
+/// Splits a qualified key into its section and local name at the last `.`.
+///
+fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.rfind('.') {
+        Some(pos) => (Some(&key[..pos]), &key[pos + 1..]),
+        None => (None, key),
+    }
+}
+
+/* ERROR */
+///
+/// A problem found on a specific (1-based) line while parsing a config.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+impl ParseError {
+    fn new(line: usize, message: impl Into<String>) -> ParseError {
+        ParseError { line, message: message.into() }
+    }
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+impl Error for ParseError {}
+
+///
+/// Every error found in a config document. Returned inside an
+/// `std::io::Error` of kind `InvalidData` by `ConfigReader::read`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
             }
-        })
+            write!(f, "{}", e)?;
+        }
+        Ok(())
     }
 }
+impl Error for ParseErrors {}
+
 ///
 /// A service for managing a configuaration
 ///
-pub struct KeyValueConfigService {}
+/// The format is INI-like:
+///
+/// ```text
+/// # comment
+/// ; also a comment
+/// name = plain value
+///
+/// [server]
+/// url = "http://a/?b=c"
+/// motd = "tab\tquote\" newline\n"
+/// ```
+///
+/// Comments must be on their own line. Quoted values support the
+/// escapes `\\`, `\"`, `\n`, `\t` and `\r`; unquoted values are taken
+/// verbatim after trimming, so `#` or `;` within them is not a comment.
+///
+/// With a `Schema` attached, `read` also rejects configs that don't
+/// match it, reporting every violation.
+///
+#[derive(Default)]
+pub struct KeyValueConfigService {
+    schema: Option<Schema>,
+}
 impl KeyValueConfigService {
     pub fn new() -> KeyValueConfigService {
-        KeyValueConfigService {}
+        KeyValueConfigService { schema: None }
+    }
+
+    pub fn with_schema(schema: Schema) -> KeyValueConfigService {
+        KeyValueConfigService { schema: Some(schema) }
+    }
+
+    ///
+    /// Parses a config document, collecting all errors instead of
+    /// stopping at the first one.
+    ///
+    pub fn parse(&self, input: &str) -> Result<Config, ParseErrors> {
+        let mut values: Vec<(String, String)> = vec![];
+        let mut layout = vec![];
+        let mut errors = vec![];
+        let mut section: Option<String> = None;
+
+        for (i, raw) in input.lines().enumerate() {
+            let nr = i + 1;
+            let line = raw.trim();
+
+            if line.is_empty() {
+                layout.push(Line::Blank);
+            } else if line.starts_with('#') || line.starts_with(';') {
+                layout.push(Line::Comment(line.to_string()));
+            } else if line.starts_with('[') {
+                match line.strip_suffix(']').map(|s| s[1..].trim()) {
+                    Some(name) if !name.is_empty() => {
+                        section = Some(name.to_string());
+                        layout.push(Line::Section(name.to_string()));
+                    }
+                    Some(_) => errors.push(ParseError::new(nr, "empty section name")),
+                    None => errors.push(ParseError::new(nr, "section header is missing `]`")),
+                }
+            } else if let Some(pos) = line.find('=') {
+                let key = line[..pos].trim();
+                if key.is_empty() {
+                    errors.push(ParseError::new(nr, "missing key before `=`"));
+                    continue;
+                }
+                let key = match &section {
+                    Some(s) => format!("{}.{}", s, key),
+                    None => key.to_string(),
+                };
+                if values.iter().any(|(k, _)| *k == key) {
+                    errors.push(ParseError::new(nr, format!("duplicate key `{}`", key)));
+                    continue;
+                }
+                match parse_value(line[pos + 1..].trim()) {
+                    Ok(value) => {
+                        layout.push(Line::Entry(values.len()));
+                        values.push((key, value));
+                    }
+                    Err(msg) => errors.push(ParseError::new(nr, msg)),
+                }
+            } else {
+                errors.push(ParseError::new(nr, "expected `key = value`, `[section]` or a comment"));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Config { values, layout, defaults: vec![] })
+        } else {
+            Err(ParseErrors(errors))
+        }
+    }
+}
+
+///
+/// Reads a value, unquoting and unescaping it if it starts with `"`.
+///
+fn parse_value(raw: &str) -> Result<String, String> {
+    let quoted = match raw.strip_prefix('"') {
+        Some(rest) => rest,
+        None => return Ok(raw.to_string()),
+    };
+
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let rest = chars.as_str().trim();
+                return if rest.is_empty() {
+                    Ok(value)
+                } else {
+                    Err(format!("unexpected `{}` after closing quote", rest))
+                };
+            }
+            '\\' => match chars.next() {
+                Some('\\') => value.push('\\'),
+                Some('"') => value.push('"'),
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some(other) => return Err(format!("unknown escape `\\{}`", other)),
+                None => return Err("unterminated quoted value".to_string()),
+            },
+            c => value.push(c),
+        }
+    }
+    Err("unterminated quoted value".to_string())
+}
+
+///
+/// Quotes a value if reading it back verbatim would change it.
+///
+fn format_value(value: &str) -> String {
+    let needs_quotes = value != value.trim()
+        || value.starts_with('"')
+        || value.contains(|c: char| c.is_control());
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
     }
+    quoted.push('"');
+    quoted
 }
+
 impl ConfigWriter for KeyValueConfigService {
     fn write(&self, config: Config, mut to: &mut impl Write) -> std::io::Result<()> {
-        for v in config.values {
-            writeln!(&mut to, "{0}={1}", v.0, v.1)?;
+        let mut section: Option<&str> = None;
+        for line in &config.layout {
+            match line {
+                Line::Blank => writeln!(&mut to)?,
+                Line::Comment(c) => writeln!(&mut to, "{}", c)?,
+                Line::Section(s) => {
+                    section = Some(s);
+                    writeln!(&mut to, "[{}]", s)?;
+                }
+                Line::Entry(i) => {
+                    let (key, value) = &config.values[*i];
+                    let key = match section {
+                        Some(s) => &key[s.len() + 1..],
+                        None => key,
+                    };
+                    writeln!(&mut to, "{0}={1}", key, format_value(value))?;
+                }
+            }
         }
 
         Ok(())
@@ -67,20 +394,15 @@ impl ConfigReader for KeyValueConfigService {
         let mut buffer = String::new();
         from.read_to_string(&mut buffer)?;
 
-        let values: Vec<(String, String)> = buffer
-            .split_terminator("\n")
-            .map(|line| line.trim())
-            .filter(|line| {
-                let pos = line.find("=").unwrap_or(0);
-                pos > 0 && pos < line.len() - 1
-            })
-            .map(|line| {
-                let parts = line.split("=").collect::<Vec::<&str>>();
-                (parts[0].to_string(), parts[1].to_string())
-            })
-            .collect();
-
-        Ok(Config::new(values))
+        let config = self
+            .parse(&buffer)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if let Some(schema) = &self.schema {
+            schema
+                .validate(&config)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(config)
     }
 }
 
@@ -92,12 +414,13 @@ mod tests {
     #[test]
     fn config_get_value() {
         let config = Config::new(vec![("hello".to_string(), "world".to_string())]);
-        
+
         assert_eq!(config.get("hello"), Some("world".to_string()));
         assert_eq!(config.get("HELLO"), None);
     }
 
     #[test]
+    #[allow(non_snake_case)]
     fn KeyValueConfigService_write_config() {
         let config = Config::new(vec![("hello".to_string(), "world".to_string())]);
         let service = KeyValueConfigService::new();
@@ -111,6 +434,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(non_snake_case)]
     fn KeyValueConfigService_read_config() {
         let service = KeyValueConfigService::new();
         let readable = &format!("{}\n{}", "hello=world", "a=b").into_bytes();
@@ -126,5 +450,84 @@ mod tests {
             ]
         )
     }
-}
 
+    #[test]
+    fn key_value_config_service_read_sections_and_quotes() {
+        let service = KeyValueConfigService::new();
+        let input = "# top\nname = app\n\n[server]\n; the url\nurl = http://a/?b=c\nmotd = \" hi \\\"there\\\"\\n\"\n";
+        let config = service.parse(input).expect("Couldn't parse");
+
+        assert_eq!(config.get("name"), Some("app".to_string()));
+        assert_eq!(config.get("server.url"), Some("http://a/?b=c".to_string()));
+        assert_eq!(config.get("server.motd"), Some(" hi \"there\"\n".to_string()));
+    }
+
+    #[test]
+    fn key_value_config_service_reports_errors_by_line() {
+        let service = KeyValueConfigService::new();
+        let input = "a=1\nno equals\n[broken\n=x\nb=\"open\na=2\nc=\"x\" y\n";
+        let errors = service.parse(input).unwrap_err();
+
+        assert_eq!(
+            errors.0.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 3, 4, 5, 6, 7]
+        );
+
+        let err = service.read(&mut Cursor::new(input)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2: "));
+    }
+
+    #[test]
+    fn key_value_config_service_validates_schema() {
+        let schema = Schema::new()
+            .field(Field::required("server.port", ValueType::Integer).range(1.0, 65535.0))
+            .field(Field::required("server.host", ValueType::String));
+        let service = KeyValueConfigService::with_schema(schema);
+
+        let err = service
+            .read(&mut Cursor::new("[server]\nport=0\n"))
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "server.port: `0` is out of range 1..=65535\nserver.host: required key is missing"
+        );
+
+        let config = service
+            .read(&mut Cursor::new("[server]\nport=80\nhost=a\n"))
+            .expect("Couldn't read a valid config");
+        assert_eq!(config.get("server.host"), Some("a".to_string()));
+    }
+
+    #[test]
+    fn key_value_config_service_round_trip() {
+        let service = KeyValueConfigService::new();
+        let input = "; header\nname=app\n\n[server]\n# the url\nurl=http://a/?b=c\nmotd=\" padded\\t\"\n\n[db]\nhost=localhost\n";
+        let config = service.parse(input).expect("Couldn't parse");
+        let mut target = vec![];
+
+        service.write(config.clone(), &mut target).expect("Couldn't write");
+        let written = String::from_utf8(target).unwrap();
+
+        assert_eq!(written, input);
+        assert_eq!(service.parse(&written).unwrap(), config);
+    }
+
+    #[test]
+    fn key_value_config_service_write_groups_sections() {
+        let config = Config::new(vec![
+            ("server.port".to_string(), "80".to_string()),
+            ("name".to_string(), " app".to_string()),
+            ("server.host".to_string(), "a".to_string()),
+        ]);
+        let service = KeyValueConfigService::new();
+        let mut target = vec![];
+
+        service.write(config, &mut target).unwrap();
+        assert_eq!(
+            String::from_utf8(target).unwrap(),
+            "name=\" app\"\n[server]\nport=80\nhost=a\n"
+        );
+    }
+}
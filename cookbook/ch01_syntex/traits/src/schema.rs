@@ -0,0 +1,444 @@
+use std::error::Error;
+use std::fmt;
+
+use regex::Regex;
+
+use crate::{Config, ValueError, ValueGetter};
+
+///
+/// The type a value must be readable as, using `Config`'s typed getters.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueType {
+    String,
+    Bool,
+    Integer,
+    Float,
+    Duration,
+    List,
+}
+
+///
+/// The expectations for a single key.
+///
+#[derive(Debug, Clone)]
+pub struct Field {
+    key: String,
+    required: bool,
+    value_type: ValueType,
+    min: Option<f64>,
+    max: Option<f64>,
+    allowed: Vec<String>,
+    pattern: Option<(String, Regex)>,
+}
+impl Field {
+    pub fn required(key: &str, value_type: ValueType) -> Field {
+        Field::new(key, value_type, true)
+    }
+
+    pub fn optional(key: &str, value_type: ValueType) -> Field {
+        Field::new(key, value_type, false)
+    }
+
+    fn new(key: &str, value_type: ValueType, required: bool) -> Field {
+        Field {
+            key: key.to_string(),
+            required,
+            value_type,
+            min: None,
+            max: None,
+            allowed: vec![],
+            pattern: None,
+        }
+    }
+
+    ///
+    /// Inclusive bounds for `Integer` and `Float` values.
+    ///
+    /// # Panics
+    ///
+    /// If the field is of another type, like `min` and `max`.
+    ///
+    pub fn range(self, min: f64, max: f64) -> Field {
+        self.min(min).max(max)
+    }
+
+    pub fn min(mut self, min: f64) -> Field {
+        self.expect_type("min", &[ValueType::Integer, ValueType::Float]);
+        self.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: f64) -> Field {
+        self.expect_type("max", &[ValueType::Integer, ValueType::Float]);
+        self.max = Some(max);
+        self
+    }
+
+    ///
+    /// Restricts the value (or every item of a `List`) to these values.
+    ///
+    /// # Panics
+    ///
+    /// If the field isn't a `String` or `List`.
+    ///
+    pub fn one_of(mut self, allowed: &[&str]) -> Field {
+        self.expect_type("one_of", &[ValueType::String, ValueType::List]);
+        self.allowed = allowed.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    ///
+    /// Requires the whole value (or every item of a `List`) to match `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// If the field isn't a `String` or `List`, or `pattern` isn't a valid
+    /// regular expression, since schemas are written by developers rather
+    /// than read from input.
+    ///
+    pub fn pattern(mut self, pattern: &str) -> Field {
+        self.expect_type("pattern", &[ValueType::String, ValueType::List]);
+        let anchored = format!("^(?:{})$", pattern);
+        let regex = Regex::new(&anchored).expect("invalid schema pattern");
+        self.pattern = Some((pattern.to_string(), regex));
+        self
+    }
+
+    ///
+    /// Panics unless the field has one of the types `constraint` applies to,
+    /// so a constraint can't be silently ignored.
+    ///
+    fn expect_type(&self, constraint: &str, types: &[ValueType]) {
+        if !types.contains(&self.value_type) {
+            panic!(
+                "`{}` doesn't apply to {:?} field `{}`",
+                constraint, self.value_type, self.key
+            );
+        }
+    }
+
+    fn check(&self, config: &Config, violations: &mut Vec<Violation>) {
+        let mut fail = |message: String| violations.push(Violation::new(&self.key, message));
+        let missing = |fail: &mut dyn FnMut(String)| {
+            if self.required {
+                fail("required key is missing".to_string());
+            }
+        };
+
+        // lists may be stored under indexed keys, so `get` alone can't find them
+        let items = if self.value_type == ValueType::List {
+            match config.get_list(&self.key) {
+                Ok(items) => items,
+                Err(_) => return missing(&mut fail),
+            }
+        } else {
+            let raw = match config.get(&self.key) {
+                Some(raw) => raw,
+                None => return missing(&mut fail),
+            };
+            match self.value_type {
+                ValueType::Bool => return or_fail(config.get_bool(&self.key), &mut fail),
+                ValueType::Duration => return or_fail(config.get_duration(&self.key), &mut fail),
+                ValueType::Integer => match config.get_i64(&self.key) {
+                    Ok(i) => return self.check_range(i as f64, &raw, &mut fail),
+                    Err(e) => return fail(expected(e)),
+                },
+                ValueType::Float => match config.get_f64(&self.key) {
+                    Ok(f) => return self.check_range(f, &raw, &mut fail),
+                    Err(e) => return fail(expected(e)),
+                },
+                ValueType::String | ValueType::List => vec![raw],
+            }
+        };
+
+        for item in items {
+            if !self.allowed.is_empty() && !self.allowed.contains(&item) {
+                fail(format!("`{}` is not one of {}", item, quoted(&self.allowed)));
+            }
+            if let Some((pattern, regex)) = &self.pattern {
+                if !regex.is_match(&item) {
+                    fail(format!("`{}` does not match pattern `{}`", item, pattern));
+                }
+            }
+        }
+    }
+
+    fn check_range(&self, nr: f64, raw: &str, fail: &mut impl FnMut(String)) {
+        let below = self.min.is_some_and(|min| nr < min);
+        let above = self.max.is_some_and(|max| nr > max);
+        // NaN compares false with every bound, but is in no range
+        let nan = nr.is_nan() && (self.min.is_some() || self.max.is_some());
+        if below || above || nan {
+            let bound = |b: Option<f64>| b.map(|b| b.to_string()).unwrap_or_default();
+            fail(format!(
+                "`{}` is out of range {}..={}",
+                raw,
+                bound(self.min),
+                bound(self.max)
+            ));
+        }
+    }
+}
+
+fn or_fail<T>(result: Result<T, ValueError>, fail: &mut impl FnMut(String)) {
+    if let Err(e) = result {
+        fail(expected(e));
+    }
+}
+
+fn expected(e: ValueError) -> String {
+    match e {
+        ValueError::Invalid { value, expected, .. } => format!("expected {}, found `{}`", expected, value),
+        other => other.to_string(),
+    }
+}
+
+fn quoted(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("`{}`", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+///
+/// One way in which a config does not match its schema.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub key: String,
+    pub message: String,
+}
+impl Violation {
+    fn new(key: &str, message: impl Into<String>) -> Violation {
+        Violation { key: key.to_string(), message: message.into() }
+    }
+}
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+impl Error for Violation {}
+
+///
+/// Every violation found while validating a config.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationErrors(pub Vec<Violation>);
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", v)?;
+        }
+        Ok(())
+    }
+}
+impl Error for ValidationErrors {}
+
+///
+/// Describes the keys a `Config` is expected to have.
+///
+/// # Example
+///
+/// ```
+/// # use traits::{Config, Field, Schema, ValueType};
+/// let schema = Schema::new()
+///     .field(Field::required("server.port", ValueType::Integer).range(1.0, 65535.0))
+///     .field(Field::optional("log", ValueType::String).one_of(&["info", "debug"]));
+/// let config = Config::new(vec![("server.port".to_string(), "0".to_string())]);
+///
+/// let errors = schema.validate(&config).unwrap_err();
+/// assert_eq!(errors.to_string(), "server.port: `0` is out of range 1..=65535");
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    fields: Vec<Field>,
+    deny_unknown: bool,
+}
+impl Schema {
+    pub fn new() -> Schema {
+        Schema { fields: vec![], deny_unknown: false }
+    }
+
+    pub fn field(mut self, field: Field) -> Schema {
+        self.fields.push(field);
+        self
+    }
+
+    ///
+    /// Reports keys that no field describes, which catches typos.
+    ///
+    pub fn deny_unknown_keys(mut self) -> Schema {
+        self.deny_unknown = true;
+        self
+    }
+
+    ///
+    /// Checks `config` against every field and returns all violations.
+    ///
+    pub fn validate(&self, config: &Config) -> Result<(), ValidationErrors> {
+        let mut violations = vec![];
+        for field in &self.fields {
+            field.check(config, &mut violations);
+        }
+
+        if self.deny_unknown {
+            for (key, _) in &config.values {
+                let known = self.fields.iter().any(|f| {
+                    f.key == *key
+                        || (f.value_type == ValueType::List
+                            && key
+                                .strip_prefix(f.key.as_str())
+                                .and_then(|rest| rest.strip_prefix('.'))
+                                .is_some_and(|i| i.parse::<usize>().is_ok()))
+                });
+                if !known {
+                    violations.push(Violation::new(key, "unknown key"));
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(violations))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(values: &[(&str, &str)]) -> Config {
+        Config::new(
+            values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    fn schema() -> Schema {
+        Schema::new()
+            .field(Field::required("name", ValueType::String).pattern("[a-z][a-z0-9-]*"))
+            .field(Field::required("server.port", ValueType::Integer).range(1.0, 65535.0))
+            .field(Field::optional("server.ratio", ValueType::Float).max(1.0))
+            .field(Field::optional("server.tls", ValueType::Bool))
+            .field(Field::required("timeout", ValueType::Duration))
+            .field(Field::optional("log", ValueType::String).one_of(&["info", "debug"]))
+            .field(Field::optional("hosts", ValueType::List).pattern("[a-z.]+"))
+    }
+
+    #[test]
+    fn schema_accepts_valid_config() {
+        let config = config(&[
+            ("name", "my-app"),
+            ("server.port", "8080"),
+            ("server.ratio", "0.5"),
+            ("timeout", "30s"),
+            ("log", "debug"),
+            ("hosts.0", "a.example"),
+            ("hosts.1", "b.example"),
+        ]);
+
+        assert_eq!(schema().deny_unknown_keys().validate(&config), Ok(()));
+    }
+
+    #[test]
+    fn schema_reports_every_violation() {
+        let config = config(&[
+            ("name", "My App"),
+            ("server.port", "70000"),
+            ("server.ratio", "lots"),
+            ("server.tls", "maybe"),
+            ("log", "trace"),
+            ("hosts", "a.example, B"),
+            ("extra", "1"),
+        ]);
+        let errors = schema().deny_unknown_keys().validate(&config).unwrap_err();
+
+        assert_eq!(
+            errors.to_string(),
+            [
+                "name: `My App` does not match pattern `[a-z][a-z0-9-]*`",
+                "server.port: `70000` is out of range 1..=65535",
+                "server.ratio: expected f64, found `lots`",
+                "server.tls: expected a boolean (true/false, yes/no, on/off, 1/0), found `maybe`",
+                "timeout: required key is missing",
+                "log: `trace` is not one of `info`, `debug`",
+                "hosts: `B` does not match pattern `[a-z.]+`",
+                "extra: unknown key",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn schema_rejects_nan_outside_any_range() {
+        let schema = Schema::new()
+            .field(Field::optional("ratio", ValueType::Float).max(1.0))
+            .field(Field::optional("weight", ValueType::Float));
+        let errors = schema.validate(&config(&[("ratio", "NaN"), ("weight", "NaN")])).unwrap_err();
+
+        assert_eq!(errors.to_string(), "ratio: `NaN` is out of range ..=1");
+    }
+
+    #[test]
+    fn schema_checks_indexed_list_items() {
+        let config = config(&[
+            ("name", "app"),
+            ("server.port", "80"),
+            ("timeout", "1s"),
+            ("hosts.0", "a.example"),
+            ("hosts.1", "B"),
+        ]);
+        let schema = schema().field(Field::required("tags", ValueType::List).one_of(&["x", "y"]));
+
+        assert_eq!(
+            schema.validate(&config).unwrap_err().to_string(),
+            "hosts: `B` does not match pattern `[a-z.]+`\ntags: required key is missing"
+        );
+
+        let config = config.clone().with_defaults(vec![
+            ("tags.0".to_string(), "x".to_string()),
+            ("tags.1".to_string(), "z".to_string()),
+        ]);
+        assert_eq!(
+            schema.validate(&config).unwrap_err().0[1].to_string(),
+            "tags: `z` is not one of `x`, `y`"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`pattern` doesn't apply to Integer field `port`")]
+    fn schema_rejects_pattern_on_integer() {
+        Field::required("port", ValueType::Integer).pattern("[0-9]+");
+    }
+
+    #[test]
+    #[should_panic(expected = "`one_of` doesn't apply to Bool field `tls`")]
+    fn schema_rejects_one_of_on_bool() {
+        Field::optional("tls", ValueType::Bool).one_of(&["true"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`min` doesn't apply to String field `name`")]
+    fn schema_rejects_range_on_string() {
+        Field::optional("name", ValueType::String).range(1.0, 5.0);
+    }
+
+    #[test]
+    fn schema_uses_defaults() {
+        let config = config(&[("name", "app"), ("server.port", "80")])
+            .with_defaults(vec![("timeout".to_string(), "5s".to_string())]);
+
+        assert_eq!(schema().validate(&config), Ok(()));
+    }
+}
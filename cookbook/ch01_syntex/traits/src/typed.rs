@@ -0,0 +1,229 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{Config, ValueGetter};
+
+///
+/// Why a typed lookup on a `Config` failed.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueError {
+    /// The key has neither a value nor a default.
+    Missing { key: String },
+    /// The value exists but can't be read as the requested type.
+    Invalid {
+        key: String,
+        value: String,
+        expected: String,
+    },
+}
+impl ValueError {
+    fn invalid(key: &str, value: &str, expected: impl Into<String>) -> ValueError {
+        ValueError::Invalid {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: expected.into(),
+        }
+    }
+}
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueError::Missing { key } => write!(f, "missing value for key `{}`", key),
+            ValueError::Invalid { key, value, expected } => write!(
+                f,
+                "invalid value `{}` for key `{}`: expected {}",
+                value, key, expected
+            ),
+        }
+    }
+}
+impl Error for ValueError {}
+
+///
+/// Typed accessors. Each looks the key up through `get`, so declared
+/// defaults apply, and names the key and value on failure.
+///
+impl Config {
+    fn require(&self, key: &str) -> Result<String, ValueError> {
+        self.get(key).ok_or_else(|| ValueError::Missing { key: key.to_string() })
+    }
+
+    ///
+    /// Parses the value with `FromStr`, e.g. `config.get_parsed::<u16>("port")`.
+    ///
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Result<T, ValueError> {
+        let value = self.require(key)?;
+        value
+            .trim()
+            .parse()
+            .map_err(|_| ValueError::invalid(key, &value, std::any::type_name::<T>()))
+    }
+
+    ///
+    /// Accepts `true`/`false`, `yes`/`no`, `on`/`off` and `1`/`0`,
+    /// ignoring case.
+    ///
+    pub fn get_bool(&self, key: &str) -> Result<bool, ValueError> {
+        let value = self.require(key)?;
+        match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(true),
+            "false" | "no" | "off" | "0" => Ok(false),
+            _ => Err(ValueError::invalid(key, &value, "a boolean (true/false, yes/no, on/off, 1/0)")),
+        }
+    }
+
+    pub fn get_i64(&self, key: &str) -> Result<i64, ValueError> {
+        self.get_parsed(key)
+    }
+
+    pub fn get_u64(&self, key: &str) -> Result<u64, ValueError> {
+        self.get_parsed(key)
+    }
+
+    pub fn get_f64(&self, key: &str) -> Result<f64, ValueError> {
+        self.get_parsed(key)
+    }
+
+    ///
+    /// Reads durations such as `30s`, `250ms` or `1h30m`. The units are
+    /// `ms`, `s`, `m`, `h` and `d`; a bare number is rejected because its
+    /// unit would be a guess.
+    ///
+    pub fn get_duration(&self, key: &str) -> Result<Duration, ValueError> {
+        let value = self.require(key)?;
+        parse_duration(value.trim())
+            .ok_or_else(|| ValueError::invalid(key, &value, "a duration like `30s`, `250ms` or `1h30m`"))
+    }
+
+    ///
+    /// Splits a comma separated value into trimmed, non-empty items.
+    /// Without a value for `key` itself, the indexed keys `key.0`, `key.1`,
+    /// ... that arrays from JSON, TOML or YAML are read into are collected.
+    ///
+    pub fn get_list(&self, key: &str) -> Result<Vec<String>, ValueError> {
+        let value = match self.get(key) {
+            Some(value) => value,
+            None => {
+                let items: Vec<String> = (0..)
+                    .map_while(|i| self.get(&format!("{}.{}", key, i)))
+                    .collect();
+                if items.is_empty() {
+                    return Err(ValueError::Missing { key: key.to_string() });
+                }
+                return Ok(items);
+            }
+        };
+        Ok(value
+            .split(',')
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| item.to_string())
+            .collect())
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total = Duration::from_secs(0);
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let amount: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+
+        let unit = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let part = match &rest[..unit] {
+            "ms" => Duration::from_millis(amount),
+            "s" => Duration::from_secs(amount),
+            "m" => Duration::from_secs(amount.checked_mul(60)?),
+            "h" => Duration::from_secs(amount.checked_mul(60 * 60)?),
+            "d" => Duration::from_secs(amount.checked_mul(24 * 60 * 60)?),
+            _ => return None,
+        };
+        total = total.checked_add(part)?;
+        rest = &rest[unit..];
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(values: &[(&str, &str)]) -> Config {
+        Config::new(
+            values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn typed_getters_parse_values() {
+        let config = config(&[
+            ("debug", "Yes"),
+            ("port", "8080"),
+            ("offset", "-3"),
+            ("ratio", "0.25"),
+            ("timeout", "1h30m"),
+            ("hosts", "a, b,,c "),
+        ]);
+
+        assert_eq!(config.get_bool("debug"), Ok(true));
+        assert_eq!(config.get_u64("port"), Ok(8080));
+        assert_eq!(config.get_parsed::<u16>("port"), Ok(8080));
+        assert_eq!(config.get_i64("offset"), Ok(-3));
+        assert_eq!(config.get_f64("ratio"), Ok(0.25));
+        assert_eq!(config.get_duration("timeout"), Ok(Duration::from_secs(5400)));
+        assert_eq!(
+            config.get_list("hosts"),
+            Ok(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn typed_getters_name_key_and_value() {
+        let config = config(&[("port", "eighty"), ("timeout", "30")]);
+
+        assert_eq!(
+            config.get_u64("port").unwrap_err().to_string(),
+            "invalid value `eighty` for key `port`: expected u64"
+        );
+        assert!(config.get_duration("timeout").is_err());
+        assert_eq!(
+            config.get_bool("missing"),
+            Err(ValueError::Missing { key: "missing".to_string() })
+        );
+    }
+
+    #[test]
+    fn defaults_fill_missing_keys_only() {
+        let config = config(&[("port", "80")]).with_defaults(vec![
+            ("port".to_string(), "8080".to_string()),
+            ("timeout".to_string(), "30s".to_string()),
+        ]);
+
+        assert_eq!(config.get_u64("port"), Ok(80));
+        assert_eq!(config.get_duration("timeout"), Ok(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_duration("1m30s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("5 s"), None);
+        assert_eq!(parse_duration("s"), None);
+    }
+}
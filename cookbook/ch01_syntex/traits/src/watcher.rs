@@ -0,0 +1,285 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{Config, ConfigReader};
+
+///
+/// The keys that differ between two configs, in order of appearance.
+///
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfigChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+impl ConfigChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Config {
+    ///
+    /// Compares the values of this config with `new`, ignoring defaults.
+    ///
+    pub fn diff(&self, new: &Config) -> ConfigChange {
+        let find = |config: &Config, key: &str| {
+            config.values.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+        };
+
+        let mut change = ConfigChange::default();
+        for (key, old) in &self.values {
+            match find(new, key) {
+                None => change.removed.push(key.clone()),
+                Some(v) if v != *old => change.changed.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+        for (key, _) in &new.values {
+            if find(self, key).is_none() {
+                change.added.push(key.clone());
+            }
+        }
+        change
+    }
+}
+
+type Subscriber = Box<dyn Fn(&ConfigChange, &Config) + Send>;
+type ErrorHandler = Box<dyn Fn(&std::io::Error) + Send>;
+
+///
+/// Re-reads a config file when its contents change and notifies
+/// subscribers about the keys that changed.
+///
+/// Changes are detected by comparing the file's contents on every poll,
+/// which is cheap for config files and doesn't depend on the file
+/// system's timestamp resolution. A file that fails to read or parse is
+/// reported and the previous config stays in effect.
+///
+pub struct ConfigWatcher<R: ConfigReader> {
+    reader: R,
+    path: PathBuf,
+    contents: Vec<u8>,
+    current: Arc<RwLock<Config>>,
+    subscribers: Vec<Subscriber>,
+    on_error: Option<ErrorHandler>,
+}
+impl<R: ConfigReader> ConfigWatcher<R> {
+    ///
+    /// Reads the config at `path`; failing here is an error since there
+    /// is no previous config to fall back to.
+    ///
+    pub fn new(reader: R, path: impl AsRef<Path>) -> std::io::Result<ConfigWatcher<R>> {
+        let path = path.as_ref().to_path_buf();
+        let contents = fs::read(&path)?;
+        let config = reader.read(&mut contents.as_slice())?;
+
+        Ok(ConfigWatcher {
+            reader,
+            path,
+            contents,
+            current: Arc::new(RwLock::new(config)),
+            subscribers: vec![],
+            on_error: None,
+        })
+    }
+
+    ///
+    /// Calls `f` with the change and the new config after every reload
+    /// that changed at least one value.
+    ///
+    pub fn subscribe(&mut self, f: impl Fn(&ConfigChange, &Config) + Send + 'static) {
+        self.subscribers.push(Box::new(f));
+    }
+
+    ///
+    /// Calls `f` whenever a reload fails while running in the background.
+    ///
+    pub fn on_error(&mut self, f: impl Fn(&std::io::Error) + Send + 'static) {
+        self.on_error = Some(Box::new(f));
+    }
+
+    pub fn current(&self) -> Config {
+        self.current.read().expect("config lock poisoned").clone()
+    }
+
+    ///
+    /// Checks the file once. Returns the change if the config was
+    /// reloaded, or the error that kept the previous config in place.
+    ///
+    pub fn poll(&mut self) -> std::io::Result<Option<ConfigChange>> {
+        let contents = fs::read(&self.path)?;
+        if contents == self.contents {
+            return Ok(None);
+        }
+
+        // remember broken contents too, so they are reported only once
+        self.contents = contents;
+        let config = self.reader.read(&mut self.contents.as_slice())?;
+
+        let change = self.current().diff(&config);
+        *self.current.write().expect("config lock poisoned") = config.clone();
+        if change.is_empty() {
+            return Ok(None);
+        }
+
+        for subscriber in &self.subscribers {
+            subscriber(&change, &config);
+        }
+        Ok(Some(change))
+    }
+}
+impl<R: ConfigReader + Send + 'static> ConfigWatcher<R> {
+    ///
+    /// Polls every `interval` on a background thread until the returned
+    /// handle is stopped or dropped.
+    ///
+    pub fn spawn(mut self, interval: Duration) -> WatchHandle {
+        let current = Arc::clone(&self.current);
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if let Err(e) = self.poll() {
+                    if let Some(on_error) = &self.on_error {
+                        on_error(&e);
+                    }
+                }
+            }
+        });
+
+        WatchHandle { current, stop, thread: Some(thread) }
+    }
+}
+
+///
+/// Controls a watcher running in the background.
+///
+pub struct WatchHandle {
+    current: Arc<RwLock<Config>>,
+    stop: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+impl WatchHandle {
+    ///
+    /// The most recently loaded valid config.
+    ///
+    pub fn current(&self) -> Config {
+        self.current.read().expect("config lock poisoned").clone()
+    }
+
+    ///
+    /// Stops polling and waits for the background thread to finish.
+    ///
+    pub fn stop(self) {}
+}
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyValueConfigService, ValueGetter};
+    use std::sync::Mutex;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("traits-{}-{}.conf", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn config_diff_lists_keys() {
+        let old = Config::new(vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("c".to_string(), "3".to_string()),
+        ]);
+        let new = Config::new(vec![
+            ("d".to_string(), "4".to_string()),
+            ("b".to_string(), "20".to_string()),
+            ("c".to_string(), "3".to_string()),
+        ]);
+
+        assert_eq!(
+            old.diff(&new),
+            ConfigChange {
+                added: vec!["d".to_string()],
+                removed: vec!["a".to_string()],
+                changed: vec!["b".to_string()],
+            }
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn config_watcher_reloads_and_notifies() {
+        let path = temp_file("poll", "a=1\nb=2\n");
+        let mut watcher = ConfigWatcher::new(KeyValueConfigService::new(), &path).unwrap();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let sink = Arc::clone(&seen);
+        watcher.subscribe(move |change, config| {
+            sink.lock().unwrap().push((change.clone(), config.get("b")));
+        });
+
+        assert_eq!(watcher.poll().unwrap(), None);
+
+        fs::write(&path, "# only a comment changed\na=1\nb=2\n").unwrap();
+        assert_eq!(watcher.poll().unwrap(), None);
+
+        fs::write(&path, "b=3\nc=4\n").unwrap();
+        let change = watcher.poll().unwrap().expect("config should have changed");
+        assert_eq!(change.added, vec!["c".to_string()]);
+        assert_eq!(change.removed, vec!["a".to_string()]);
+        assert_eq!(change.changed, vec!["b".to_string()]);
+        assert_eq!(watcher.current().get("b"), Some("3".to_string()));
+        assert_eq!(*seen.lock().unwrap(), vec![(change, Some("3".to_string()))]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn config_watcher_keeps_previous_config_on_error() {
+        let path = temp_file("invalid", "a=1\n");
+        let mut watcher = ConfigWatcher::new(KeyValueConfigService::new(), &path).unwrap();
+
+        fs::write(&path, "a=1\nbroken\n").unwrap();
+        assert!(watcher.poll().is_err());
+        assert_eq!(watcher.poll().unwrap(), None);
+        assert_eq!(watcher.current().get("a"), Some("1".to_string()));
+
+        fs::write(&path, "a=2\n").unwrap();
+        assert!(watcher.poll().unwrap().is_some());
+        assert_eq!(watcher.current().get("a"), Some("2".to_string()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn config_watcher_runs_in_background() {
+        let path = temp_file("spawn", "a=1\n");
+        let mut watcher = ConfigWatcher::new(KeyValueConfigService::new(), &path).unwrap();
+        let (tx, rx) = mpsc::channel();
+        watcher.subscribe(move |change, _| tx.send(change.clone()).unwrap());
+
+        let handle = watcher.spawn(Duration::from_millis(10));
+        fs::write(&path, "a=2\n").unwrap();
+
+        let change = rx.recv_timeout(Duration::from_secs(5)).expect("no change seen");
+        assert_eq!(change.changed, vec!["a".to_string()]);
+        assert_eq!(handle.current().get("a"), Some("2".to_string()));
+        handle.stop();
+
+        fs::remove_file(&path).unwrap();
+    }
+}
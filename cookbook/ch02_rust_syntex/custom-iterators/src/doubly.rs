@@ -0,0 +1,593 @@
+//!
+//! A doubly-linked list with borrowing iterators and a cursor.
+//!
+//! Unlike `List`, the nodes are owned through raw pointers instead of
+//! `Rc<RefCell<_>>`, which is what makes handing out plain `&T` and
+//! `&mut T` possible and removes the `Clone` bound.
+//!
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+///
+/// A doubly-linked list, supporting O(1) insertion and removal at both
+/// ends and, through `CursorMut`, anywhere in between.
+///
+/// # Example
+///
+/// ```
+/// use custom_iterators::DoublyLinkedList;
+///
+/// let mut list = DoublyLinkedList::new_empty();
+/// list.append(2);
+/// list.push_front(1);
+/// list.append(3);
+///
+/// assert_eq!(list.iter().rev().collect::<Vec<_>>(), vec![&3, &2, &1]);
+/// assert_eq!(list.pop_back(), Some(3));
+/// ```
+///
+pub struct DoublyLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    length: usize,
+    _owns: PhantomData<Box<Node<T>>>,
+}
+
+// The list owns its nodes exclusively, like a `Box<T>` would.
+unsafe impl<T: Send> Send for DoublyLinkedList<T> {}
+unsafe impl<T: Sync> Sync for DoublyLinkedList<T> {}
+
+impl<T> DoublyLinkedList<T> {
+    ///
+    /// Creates a new empty list.
+    ///
+    pub fn new_empty() -> DoublyLinkedList<T> {
+        DoublyLinkedList { head: None, tail: None, length: 0, _owns: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    ///
+    /// Appends a node to the list at the end.
+    ///
+    pub fn append(&mut self, value: T) {
+        self.link_between(self.tail, None, value);
+    }
+
+    ///
+    /// Inserts a node at the start of the list.
+    ///
+    pub fn push_front(&mut self, value: T) {
+        self.link_between(None, self.head, value);
+    }
+
+    ///
+    /// Removes the list's head and returns the result.
+    ///
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|head| unsafe { self.unlink(head) })
+    }
+
+    ///
+    /// Removes the list's tail and returns the result.
+    ///
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|tail| unsafe { self.unlink(tail) })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|head| unsafe { &(*head.as_ptr()).value })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|head| unsafe { &mut (*head.as_ptr()).value })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|tail| unsafe { &(*tail.as_ptr()).value })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.tail.map(|tail| unsafe { &mut (*tail.as_ptr()).value })
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    ///
+    /// Iterates over references to the values, front to back.
+    ///
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { head: self.head, tail: self.tail, remaining: self.length, _list: PhantomData }
+    }
+
+    ///
+    /// Iterates over mutable references to the values, front to back.
+    ///
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { head: self.head, tail: self.tail, remaining: self.length, _list: PhantomData }
+    }
+
+    ///
+    /// A cursor pointing at the first element (or the "ghost" position
+    /// if the list is empty).
+    ///
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut { current: self.head, index: 0, list: self }
+    }
+
+    ///
+    /// A cursor pointing at the last element (or the "ghost" position
+    /// if the list is empty).
+    ///
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let index = self.length.saturating_sub(1);
+        CursorMut { current: self.tail, index, list: self }
+    }
+
+    ///
+    /// Allocates a node and links it in between `prev` and `next`, which
+    /// must be adjacent in this list (`None` standing for either end).
+    ///
+    fn link_between(&mut self, prev: Link<T>, next: Link<T>, value: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node { value, prev, next })));
+        unsafe {
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(node),
+                None => self.head = Some(node),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(node),
+                None => self.tail = Some(node),
+            }
+        }
+        self.length += 1;
+    }
+
+    ///
+    /// Unlinks `node` and frees it.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be part of this list.
+    ///
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = Box::from_raw(node.as_ptr());
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.length -= 1;
+        node.value
+    }
+}
+impl<T> Drop for DoublyLinkedList<T> {
+    fn drop(&mut self) {
+        // iteratively, so long lists don't recurse through their nodes
+        self.clear();
+    }
+}
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        DoublyLinkedList::new_empty()
+    }
+}
+impl<T: Clone> Clone for DoublyLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+impl<T: fmt::Debug> fmt::Debug for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+impl<T: PartialEq> PartialEq for DoublyLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for DoublyLinkedList<T> {}
+impl<T> Extend<T> for DoublyLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.append(value);
+        }
+    }
+}
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DoublyLinkedList::new_empty();
+        list.extend(iter);
+        list
+    }
+}
+
+///
+/// A borrowing iterator over the list.
+///
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    remaining: usize,
+    _list: PhantomData<&'a T>,
+}
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.remaining -= 1;
+            self.head = node.next;
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.remaining -= 1;
+            self.tail = node.prev;
+            &node.value
+        })
+    }
+}
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+///
+/// A mutably borrowing iterator over the list.
+///
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    remaining: usize,
+    _list: PhantomData<&'a mut T>,
+}
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.remaining -= 1;
+            self.head = node.next;
+            &mut node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.remaining -= 1;
+            self.tail = node.prev;
+            &mut node.value
+        })
+    }
+}
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+///
+/// An iterator for the doubly-linked list. Consumes the list.
+///
+pub struct IntoIter<T> {
+    list: DoublyLinkedList<T>,
+}
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.length, Some(self.list.length))
+    }
+}
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+///
+/// A cursor that can move freely over the list and insert or remove
+/// elements at its position in O(1).
+///
+/// Besides pointing at an element, the cursor can be on a "ghost"
+/// position between the tail and the head, where `current` is `None`.
+/// Moving past either end lands on the ghost, and moving again wraps
+/// around to the other end.
+///
+/// # Example
+///
+/// ```
+/// use custom_iterators::DoublyLinkedList;
+///
+/// let mut list: DoublyLinkedList<i32> = vec![1, 2, 4].into_iter().collect();
+/// let mut cursor = list.cursor_front_mut();
+/// cursor.move_next();
+/// cursor.insert_after(3);
+/// assert_eq!(cursor.remove_current(), Some(2));
+/// assert_eq!(cursor.current(), Some(&mut 3));
+///
+/// assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 3, 4]);
+/// ```
+///
+pub struct CursorMut<'a, T> {
+    current: Link<T>,
+    index: usize,
+    list: &'a mut DoublyLinkedList<T>,
+}
+impl<'a, T> CursorMut<'a, T> {
+    ///
+    /// The position of the current element, `None` on the ghost.
+    ///
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        next.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).prev };
+                self.index = self.index.saturating_sub(1);
+            }
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.length.saturating_sub(1);
+            }
+        }
+    }
+
+    ///
+    /// Inserts after the current element, or at the front on the ghost.
+    ///
+    pub fn insert_after(&mut self, value: T) {
+        match self.current {
+            Some(node) => {
+                let next = unsafe { (*node.as_ptr()).next };
+                self.list.link_between(Some(node), next, value);
+            }
+            None => self.list.push_front(value),
+        }
+    }
+
+    ///
+    /// Inserts before the current element, or at the back on the ghost.
+    ///
+    pub fn insert_before(&mut self, value: T) {
+        match self.current {
+            Some(node) => {
+                let prev = unsafe { (*node.as_ptr()).prev };
+                self.list.link_between(prev, Some(node), value);
+                self.index += 1;
+            }
+            None => self.list.append(value),
+        }
+    }
+
+    ///
+    /// Removes the current element and moves to the one after it.
+    /// Returns `None` and does nothing on the ghost.
+    ///
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        self.current = unsafe { (*node.as_ptr()).next };
+        Some(unsafe { self.list.unlink(node) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_list(n: usize) -> DoublyLinkedList<usize> {
+        (1..=n).collect()
+    }
+
+    #[test]
+    fn test_doubly_push_and_pop_both_ends() {
+        let mut list = DoublyLinkedList::new_empty();
+        assert_eq!(list.pop_back(), None);
+
+        list.append(2);
+        list.push_front(1);
+        list.append(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&3));
+
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_doubly_borrowing_iterators() {
+        let mut list = new_list(4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+        assert_eq!(list.iter().rev().collect::<Vec<_>>(), vec![&4, &3, &2, &1]);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        if let Some(last) = list.iter_mut().next_back() {
+            *last += 1;
+        }
+        assert_eq!(list.into_iter().rev().collect::<Vec<_>>(), vec![41, 30, 20, 10]);
+    }
+
+    #[test]
+    fn test_doubly_no_clone_bound() {
+        struct NotClone(usize);
+
+        let mut list = DoublyLinkedList::new_empty();
+        list.append(NotClone(1));
+        list.push_front(NotClone(0));
+        assert_eq!(list.iter().map(|n| n.0).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_doubly_cursor_insert_and_remove() {
+        let mut list = new_list(3);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.insert_before(15);
+        cursor.insert_after(25);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_prev(), Some(&mut 15));
+        assert_eq!(cursor.peek_next(), Some(&mut 25));
+
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 25));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_after(0);
+        cursor.insert_before(4);
+        assert_eq!(cursor.peek_next(), Some(&mut 0));
+        assert_eq!(cursor.peek_prev(), Some(&mut 4));
+
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(5));
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.current(), None);
+
+        assert_eq!(list, vec![0, 1, 15, 25, 3].into_iter().collect());
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(list.iter().rev().count(), 5);
+    }
+
+    #[test]
+    fn test_doubly_cursor_on_empty_list() {
+        let mut list = DoublyLinkedList::new_empty();
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.current(), None);
+        cursor.move_next();
+        cursor.move_prev();
+        cursor.insert_before(1);
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(format!("{:?}", list), "[1]");
+    }
+}
@@ -14,6 +14,10 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+pub mod doubly;
+
+pub use doubly::DoublyLinkedList;
+
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
 
 #[derive(Clone)]
@@ -24,7 +28,7 @@ struct Node<T> where T: Sized + Clone {
 impl<T> Node<T> where T: Sized + Clone {
     fn new(value: T) -> Rc<RefCell<Node<T>>> {
         Rc::new(RefCell::new(Node {
-            value: value,
+            value,
             next: None,
         }))
     }
@@ -163,7 +167,7 @@ impl<T> ConsumingListIterator<T> where T: Clone + Sized {
     /// Create a new iterator for this list
     ///
     fn new(list: List<T>) -> ConsumingListIterator<T> {
-        ConsumingListIterator { list: list }
+        ConsumingListIterator { list }
     }
 }
 impl<T> Iterator for ConsumingListIterator<T> where T: Clone + Sized {
@@ -200,6 +204,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_fold)]
     fn test_list_iterator() {
         let list = new_list(4, None);
         assert_eq!(list.length, 4);
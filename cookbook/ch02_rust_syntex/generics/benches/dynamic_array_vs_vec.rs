@@ -0,0 +1,117 @@
+#[macro_use]
+extern crate criterion;
+use criterion::black_box;
+use criterion::Criterion;
+use generics::{DynamicArray, Doubling, Exact, FixedStep, GrowthPolicy, OneAndAHalf};
+
+const ITEMS: u64 = 100_000;
+
+fn bench_append(c: &mut Criterion) {
+    c.bench_function("100k appends (DynamicArray)", |b| {
+        b.iter(|| {
+            let mut list = DynamicArray::new_empty();
+            for i in 0..ITEMS {
+                list.append(black_box(i));
+            }
+            list
+        })
+    });
+    c.bench_function("100k pushes (Vec)", |b| {
+        b.iter(|| {
+            let mut list = Vec::new();
+            for i in 0..ITEMS {
+                list.push(black_box(i));
+            }
+            list
+        })
+    });
+}
+
+fn append_all<G: GrowthPolicy>(policy: G) -> DynamicArray<u64, G> {
+    let mut list = DynamicArray::with_policy(policy);
+    for i in 0..ITEMS {
+        list.append(black_box(i));
+    }
+    list
+}
+
+fn bench_growth_policies(c: &mut Criterion) {
+    c.bench_function("100k appends (doubling)", |b| b.iter(|| append_all(Doubling)));
+    c.bench_function("100k appends (one and a half)", |b| b.iter(|| append_all(OneAndAHalf)));
+    c.bench_function("100k appends (fixed step of 4096)", |b| {
+        b.iter(|| append_all(FixedStep(4096)))
+    });
+    c.bench_function("100k appends (reserved)", |b| {
+        b.iter(|| {
+            let mut list = DynamicArray::with_capacity_and_policy(ITEMS as usize, Exact);
+            for i in 0..ITEMS {
+                list.append(black_box(i));
+            }
+            list
+        })
+    });
+}
+
+fn bench_append_strings(c: &mut Criterion) {
+    c.bench_function("10k string appends (DynamicArray)", |b| {
+        b.iter(|| {
+            let mut list = DynamicArray::new_empty();
+            for i in 0..10_000 {
+                list.append(i.to_string());
+            }
+            list
+        })
+    });
+    c.bench_function("10k string pushes (Vec)", |b| {
+        b.iter(|| {
+            let mut list = Vec::new();
+            for i in 0..10_000 {
+                list.push(i.to_string());
+            }
+            list
+        })
+    });
+}
+
+fn bench_insert_front(c: &mut Criterion) {
+    c.bench_function("1k inserts at the front (DynamicArray)", |b| {
+        b.iter(|| {
+            let mut list = DynamicArray::new_empty();
+            for i in 0..1_000_u64 {
+                list.insert(0, black_box(i));
+            }
+            list
+        })
+    });
+    c.bench_function("1k inserts at the front (Vec)", |b| {
+        b.iter(|| {
+            let mut list = Vec::new();
+            for i in 0..1_000_u64 {
+                list.insert(0, black_box(i));
+            }
+            list
+        })
+    });
+}
+
+fn bench_sum(c: &mut Criterion) {
+    let array: DynamicArray<u64> = (0..ITEMS).collect();
+    let vec: Vec<u64> = (0..ITEMS).collect();
+    c.bench_function("sum of 100k (DynamicArray)", move |b| {
+        b.iter(|| black_box(&array).iter().sum::<u64>())
+    });
+    c.bench_function("sum of 100k (Vec)", move |b| {
+        b.iter(|| black_box(&vec).iter().sum::<u64>())
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_append,
+    bench_growth_policies,
+    bench_append_strings,
+    bench_insert_front,
+    bench_sum
+);
+
+criterion_main!(benches);
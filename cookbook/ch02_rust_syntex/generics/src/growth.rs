@@ -0,0 +1,92 @@
+use std::cmp;
+
+///
+/// Minimum size of a dynamic array growing by `OneAndAHalf`.
+///
+const MIN_SIZE: usize = 10;
+
+///
+/// Decides how much a `DynamicArray` allocates when it runs out of room.
+///
+pub trait GrowthPolicy {
+    ///
+    /// The capacity to grow to from `capacity`, when at least `required`
+    /// slots are needed. Results below `required` are ignored.
+    ///
+    fn next_capacity(&self, capacity: usize, required: usize) -> usize;
+}
+
+///
+/// Doubles the capacity, so `n` appends cost `O(log n)` allocations.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Doubling;
+impl GrowthPolicy for Doubling {
+    fn next_capacity(&self, capacity: usize, required: usize) -> usize {
+        cmp::max(capacity.saturating_mul(2), required)
+    }
+}
+
+///
+/// Grows the capacity by half, starting with 10 slots. Wastes less memory
+/// than `Doubling` for a few more allocations.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OneAndAHalf;
+impl GrowthPolicy for OneAndAHalf {
+    fn next_capacity(&self, capacity: usize, required: usize) -> usize {
+        cmp::max(capacity.saturating_add(capacity >> 1), cmp::max(required, MIN_SIZE))
+    }
+}
+
+///
+/// Adds the same number of slots every time, so `n` appends cost `O(n)`
+/// allocations but never waste more than the step.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedStep(pub usize);
+impl GrowthPolicy for FixedStep {
+    fn next_capacity(&self, capacity: usize, required: usize) -> usize {
+        cmp::max(capacity.saturating_add(self.0), required)
+    }
+}
+
+///
+/// Allocates exactly what is required, reallocating on every append.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Exact;
+impl GrowthPolicy for Exact {
+    fn next_capacity(&self, _capacity: usize, required: usize) -> usize {
+        required
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capacities<G: GrowthPolicy>(policy: G, steps: usize) -> Vec<usize> {
+        std::iter::successors(Some(0), |&cap| Some(policy.next_capacity(cap, cap + 1)))
+            .skip(1)
+            .take(steps)
+            .collect()
+    }
+
+    #[test]
+    fn growth_policies() {
+        assert_eq!(capacities(Doubling, 5), [1, 2, 4, 8, 16]);
+        assert_eq!(capacities(OneAndAHalf, 5), [10, 15, 22, 33, 49]);
+        assert_eq!(capacities(FixedStep(8), 4), [8, 16, 24, 32]);
+        assert_eq!(capacities(FixedStep(0), 3), [1, 2, 3]);
+        assert_eq!(capacities(Exact, 3), [1, 2, 3]);
+    }
+
+    #[test]
+    fn growth_policies_respect_required() {
+        assert_eq!(Doubling.next_capacity(4, 100), 100);
+        assert_eq!(OneAndAHalf.next_capacity(10, 100), 100);
+        assert_eq!(FixedStep(8).next_capacity(8, 100), 100);
+        assert_eq!(Doubling.next_capacity(usize::MAX / 2 + 1, 5), usize::MAX);
+    }
+}
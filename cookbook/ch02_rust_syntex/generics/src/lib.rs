@@ -1,124 +1,523 @@
-use std::boxed::Box;
+mod growth;
+pub use growth::{Doubling, Exact, FixedStep, GrowthPolicy, OneAndAHalf};
+#[cfg(feature = "serde")]
+mod serialize;
+
+use std::alloc::{self, Layout};
 use std::cmp;
-use std::ops::Index;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::ptr::{self, NonNull};
+use std::slice::{self, SliceIndex};
 
 ///
-/// Minimum size of a dynamic array.
+/// An allocation for `cap` values of `T`, none of which it initializes
+/// or drops. Zero-sized types never allocate and get an infinite capacity.
 ///
-const MIN_SIZE: usize = 10;
+struct RawBuf<T> {
+    ptr: NonNull<T>,
+    cap: usize,
+    _owns: PhantomData<T>,
+}
+impl<T> RawBuf<T> {
+    fn new() -> RawBuf<T> {
+        RawBuf {
+            ptr: NonNull::dangling(),
+            cap: if mem::size_of::<T>() == 0 { usize::MAX } else { 0 },
+            _owns: PhantomData,
+        }
+    }
+    ///
+    /// Reallocates to exactly `new_cap` slots, moving the contents. The
+    /// caller must not shrink below the initialized slots.
+    ///
+    fn set_capacity(&mut self, new_cap: usize) {
+        if mem::size_of::<T>() == 0 || new_cap == self.cap {
+            return;
+        }
+        if new_cap == 0 {
+            self.release();
+            self.ptr = NonNull::dangling();
+            self.cap = 0;
+            return;
+        }
 
-///
-/// Type declaration for readability.
-///
-type Node<T> = Option<T>;
+        let new_layout = Layout::array::<T>(new_cap).expect("capacity overflow");
+        // Safety: the layout isn't zero-sized, and the old layout is the one
+        // the current pointer was allocated with.
+        let new_ptr = unsafe {
+            if self.cap == 0 {
+                alloc::alloc(new_layout)
+            } else {
+                let old_layout = Layout::array::<T>(self.cap).unwrap();
+                alloc::realloc(self.ptr.as_ptr() as *mut u8, old_layout, new_layout.size())
+            }
+        };
+        self.ptr = match NonNull::new(new_ptr as *mut T) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(new_layout),
+        };
+        self.cap = new_cap;
+    }
+
+    fn release(&mut self) {
+        if mem::size_of::<T>() != 0 && self.cap != 0 {
+            // Safety: allocated by `set_capacity` with this layout.
+            unsafe {
+                alloc::dealloc(self.ptr.as_ptr() as *mut u8, Layout::array::<T>(self.cap).unwrap());
+            }
+        }
+    }
+}
+impl<T> Drop for RawBuf<T> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
 
 ///
 /// A dynamic array that can increase in capacity and behaves like
 /// a list.
 ///
-pub struct DynamicArray<T> where T: Sized + Clone {
-    buf: Box<[Node<T>]>,
-    cap: usize,
-    pub length: usize,
+/// It dereferences to a slice, so `len`, `get`, `get_mut`, `iter`,
+/// `iter_mut`, sorting, searching etc. work as they do on a `Vec`.
+/// Like a `Vec`, it keeps its elements in one uninitialized allocation
+/// and moves them when it grows, so `T` doesn't need to be `Clone`.
+///
+/// How much it allocates when it is full is up to the `GrowthPolicy`
+/// `G`, by default growing by half like before.
+///
+/// The tests avoid large inputs so they can run under Miri
+/// (`cargo +nightly miri test`) to check the unsafe code.
+///
+pub struct DynamicArray<T, G = OneAndAHalf> {
+    buf: RawBuf<T>,
+    // the first `length` slots of `buf` are initialized
+    length: usize,
+    policy: G,
 }
-impl<T> DynamicArray<T> where T: Sized + Clone {
+// Safety: a `DynamicArray` owns its elements like a `Vec` does.
+unsafe impl<T: Send, G: Send> Send for DynamicArray<T, G> {}
+unsafe impl<T: Sync, G: Sync> Sync for DynamicArray<T, G> {}
+
+impl<T> DynamicArray<T> {
     ///
-    /// Create a new empty dynamic array.
+    /// Create a new empty dynamic array. Doesn't allocate until the
+    /// first element is added.
     ///
     pub fn new_empty() -> DynamicArray<T> {
+        DynamicArray::with_policy(OneAndAHalf)
+    }
+    ///
+    /// Create a new empty dynamic array with room for `capacity`
+    /// elements before it has to grow.
+    ///
+    pub fn with_capacity(capacity: usize) -> DynamicArray<T> {
+        DynamicArray::with_capacity_and_policy(capacity, OneAndAHalf)
+    }
+}
+
+impl<T, G> DynamicArray<T, G> {
+    ///
+    /// Create a new empty dynamic array that grows according to `policy`.
+    ///
+    pub fn with_policy(policy: G) -> DynamicArray<T, G> {
         DynamicArray {
-            buf: vec![None; MIN_SIZE].into_boxed_slice(),
+            buf: RawBuf::new(),
             length: 0,
-            cap: MIN_SIZE,
+            policy,
         }
     }
+
+    pub fn with_capacity_and_policy(capacity: usize, policy: G) -> DynamicArray<T, G> {
+        let mut array = DynamicArray::with_policy(policy);
+        array.buf.set_capacity(capacity);
+        array
+    }
     ///
-    /// A simple growth strategy, that at least doubles the size
-    /// whenever expansion is needed. Clones old content into the
-    /// new memory.
+    /// The number of elements the array can hold without reallocating.
+    /// Zero-sized elements never need memory, so it is `usize::MAX` for them.
     ///
-    fn grow(&mut self, min_cap: usize) {
-        let old_cap = self.buf.len();
-        let mut new_cap = old_cap + (old_cap >> 1);
-        let current = self.buf.clone();
+    pub fn capacity(&self) -> usize {
+        self.buf.cap
+    }
 
-        new_cap = cmp::max(new_cap, min_cap);
-        new_cap = cmp::min(new_cap, usize::max_value());
-        
-        self.cap = new_cap;
-        self.buf = vec![None; new_cap].into_boxed_slice();
-        self.buf[..current.len()].clone_from_slice(&current);
+    pub fn policy(&self) -> &G {
+        &self.policy
+    }
+    ///
+    /// Reallocate to exactly the current length, freeing unused memory.
+    ///
+    pub fn shrink_to_fit(&mut self) {
+        self.buf.set_capacity(self.length);
+    }
+
+    fn as_ptr(&self) -> *mut T {
+        self.buf.ptr.as_ptr()
+    }
+    ///
+    /// Remove and return the element at `index`, shifting all elements
+    /// after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    ///
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.length;
+        assert!(index < len, "removal index (is {}) should be < len (is {})", index, len);
+        // Safety: slot `index` is initialized and read exactly once before
+        // the slots after it are moved over it.
+        unsafe {
+            let p = self.as_ptr().add(index);
+            let value = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.length -= 1;
+            value
+        }
+    }
+    ///
+    /// Remove the last element and return it, or `None` if the array is empty.
+    ///
+    pub fn pop(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        // Safety: the slot was initialized and is now outside `length`.
+        Some(unsafe { ptr::read(self.as_ptr().add(self.length)) })
+    }
+    ///
+    /// Shorten the array to `len` elements, dropping the rest. Does
+    /// nothing if the array is already shorter.
+    ///
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.length {
+            return;
+        }
+        // Safety: the tail is initialized; it is dropped after shortening,
+        // so a panicking `drop` can't lead to a double drop.
+        unsafe {
+            let tail = ptr::slice_from_raw_parts_mut(self.as_ptr().add(len), self.length - len);
+            self.length = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+    ///
+    /// Remove all elements, keeping the capacity.
+    ///
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<T, G> DynamicArray<T, G> where G: GrowthPolicy {
+    ///
+    /// Reallocates to the capacity the policy picks for at least
+    /// `min_cap` elements, moving old content into the new memory.
+    ///
+    fn grow(&mut self, min_cap: usize) {
+        let new_cap = self.policy.next_capacity(self.buf.cap, min_cap);
+        self.buf.set_capacity(cmp::max(new_cap, min_cap));
+    }
+    ///
+    /// Make room for at least `additional` more elements, growing as
+    /// the policy says if there isn't enough.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity would overflow.
+    ///
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.length.checked_add(additional).expect("capacity overflow");
+        if required > self.buf.cap {
+            self.grow(required);
+        }
     }
     ///
     /// Append a value to the dynamic array.
     ///
     pub fn append(&mut self, value: T) {
-        if self.length == self.cap {
+        if self.length == self.buf.cap {
             self.grow(self.length + 1);
         }
-        self.buf[self.length] = Some(value);
+        // Safety: the slot is within the capacity and uninitialized.
+        unsafe { ptr::write(self.as_ptr().add(self.length), value) };
         self.length += 1;
     }
+    ///
+    /// Insert a value at `index`, shifting all elements after it to
+    /// the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    pub fn insert(&mut self, index: usize, value: T) {
+        let len = self.length;
+        assert!(index <= len, "insertion index (is {}) should be <= len (is {})", index, len);
+        if len == self.buf.cap {
+            self.grow(len + 1);
+        }
+        // Safety: slots `index..len` are initialized and there is room for one more.
+        unsafe {
+            let p = self.as_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            ptr::write(p, value);
+        }
+        self.length += 1;
+    }
+}
+
+impl<T, G> DynamicArray<T, G> where T: Clone {
     ///
     /// Retrieve an element from a specific position.
     /// Clones the element.
     ///
-    pub fn at(&mut self, index: usize) -> Node<T> {
-        if self.length > index {
-            self.buf[index].clone()
-        } else {
-            None
+    pub fn at(&self, index: usize) -> Option<T> {
+        self.get(index).cloned()
+    }
+}
+
+impl<T, G> Deref for DynamicArray<T, G> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // Safety: the first `length` slots are initialized.
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.length) }
+    }
+}
+
+impl<T, G> DerefMut for DynamicArray<T, G> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // Safety: the first `length` slots are initialized.
+        unsafe { slice::from_raw_parts_mut(self.as_ptr(), self.length) }
+    }
+}
+
+impl<T, G, I> Index<I> for DynamicArray<T, G> where I: SliceIndex<[T]> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &(**self)[index]
+    }
+}
+
+impl<T, G, I> IndexMut<I> for DynamicArray<T, G> where I: SliceIndex<[T]> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut (**self)[index]
+    }
+}
+
+impl<T, G> Clone for DynamicArray<T, G> where T: Clone, G: GrowthPolicy + Clone {
+    fn clone(&self) -> Self {
+        let mut copy = DynamicArray::with_capacity_and_policy(self.length, self.policy.clone());
+        copy.extend(self.iter().cloned());
+        copy
+    }
+}
+
+impl<T, G> fmt::Debug for DynamicArray<T, G> where T: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+///
+/// Arrays are equal if their elements are, whatever their capacity or
+/// growth policy.
+///
+impl<T, U, G, H> PartialEq<DynamicArray<U, H>> for DynamicArray<T, G> where T: PartialEq<U> {
+    fn eq(&self, other: &DynamicArray<U, H>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T, U, G> PartialEq<[U]> for DynamicArray<T, G> where T: PartialEq<U> {
+    fn eq(&self, other: &[U]) -> bool {
+        **self == *other
+    }
+}
+
+impl<T, U, G, const N: usize> PartialEq<[U; N]> for DynamicArray<T, G> where T: PartialEq<U> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        **self == other[..]
+    }
+}
+
+impl<T, U, G> PartialEq<Vec<U>> for DynamicArray<T, G> where T: PartialEq<U> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        **self == other[..]
+    }
+}
+
+impl<T, G> Eq for DynamicArray<T, G> where T: Eq {}
+
+///
+/// Hashes like a slice (and so like a `Vec`) with the same elements.
+///
+impl<T, G> Hash for DynamicArray<T, G> where T: Hash {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        (**self).hash(state)
+    }
+}
+
+impl<T, G> Default for DynamicArray<T, G> where G: Default {
+    fn default() -> Self {
+        DynamicArray::with_policy(G::default())
+    }
+}
+
+impl<T, G> Drop for DynamicArray<T, G> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, G> Extend<T> for DynamicArray<T, G> where G: GrowthPolicy {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.append(value);
         }
     }
 }
 
-impl<T> Index<usize> for DynamicArray<T> where T: Sized + Clone {
-    type Output = Node<T>;
+impl<T, G> FromIterator<T> for DynamicArray<T, G> where G: GrowthPolicy + Default {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut array = DynamicArray::default();
+        array.extend(iter);
+        array
+    }
+}
+
+impl<'a, T, G> IntoIterator for &'a DynamicArray<T, G> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
 
-    fn index(&self, index: usize) -> &Self::Output {
-        if self.length > index {
-            &self.buf[index]
-        } else {
-            &None
+impl<'a, T, G> IntoIterator for &'a mut DynamicArray<T, G> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, G> IntoIterator for DynamicArray<T, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let array = ManuallyDrop::new(self);
+        // Safety: `array` is never dropped, so the policy is moved out once.
+        drop(unsafe { ptr::read(&array.policy) });
+        IntoIter {
+            // Safety: `array` is never dropped, so the buffer is moved out once.
+            buf: unsafe { ptr::read(&array.buf) },
+            start: 0,
+            end: array.length,
         }
     }
 }
 
-impl<T> Clone for DynamicArray<T> where T: Sized + Clone {
-    fn clone(&self) -> Self {
-        DynamicArray {
-            buf: self.buf.clone(),
-            cap: self.cap,
-            length: self.length,
+///
+/// A consuming iterator over the elements of a `DynamicArray`.
+///
+pub struct IntoIter<T> {
+    buf: RawBuf<T>,
+    // the slots `start..end` are initialized and not yet yielded
+    start: usize,
+    end: usize,
+}
+// Safety: an `IntoIter` owns the remaining elements like a `DynamicArray`.
+unsafe impl<T: Send> Send for IntoIter<T> {}
+unsafe impl<T: Sync> Sync for IntoIter<T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.start += 1;
+        // Safety: the slot is initialized and is never read again.
+        Some(unsafe { ptr::read(self.buf.ptr.as_ptr().add(self.start - 1)) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
         }
+        self.end -= 1;
+        // Safety: the slot is initialized and is never read again.
+        Some(unsafe { ptr::read(self.buf.ptr.as_ptr().add(self.end)) })
+    }
+}
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> Drop for IntoIter<T> {
+    ///
+    /// Drops the elements that weren't yielded; `buf` frees the memory.
+    ///
+    fn drop(&mut self) {
+        for _ in self {}
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
+    use std::rc::Rc;
 
     #[test]
     fn dynamic_array_clone() {
         let mut list = DynamicArray::new_empty();
         list.append(3.14);
-        assert_eq!(list[0], Some(3.14));
-        assert_eq!(list[1], None);
+        assert_eq!(list[0], 3.14);
+        assert_eq!(list.get(1), None);
 
         let mut list2 = list.clone();
         list2.append(42.0);
+        assert_eq!(list.len(), 1);
+        assert_eq!(*list2, [3.14, 42.0]);
     }
 
     #[test]
     fn dynamic_array_index() {
         let mut list = DynamicArray::new_empty();
         list.append(3.14);
-        assert_eq!(list[0], Some(3.14));
+        assert_eq!(list[0], 3.14);
 
         let mut list = DynamicArray::new_empty();
         list.append("Hello");
-        assert_eq!(list[0], Some("Hello"));
-        assert_eq!(list[1], None);
+        assert_eq!(list[0], "Hello");
+        assert_eq!(list.get(1), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dynamic_array_index_out_of_bounds() {
+        let mut list = DynamicArray::new_empty();
+        list.append(1);
+        let _ = list[1];
     }
 
     #[test]
@@ -128,7 +527,7 @@ mod tests {
         sublist.append(3.14);
         list.append(sublist);
         assert_eq!(list.at(0).unwrap().at(0), Some(3.14));
-        assert_eq!(list[0].as_ref().unwrap()[0], Some(3.14));
+        assert_eq!(list[0][0], 3.14);
     }
 
     #[test]
@@ -138,7 +537,7 @@ mod tests {
         for i in 0..max {
             list.append(i as u64);
         }
-        assert_eq!(list.length, max);
+        assert_eq!(list.len(), max);
     }
 
     #[test]
@@ -147,11 +546,278 @@ mod tests {
         let max: usize = 1_000;
         for i in 0..max {
             list.append(i as u64);
-        } 
-        assert_eq!(list.length, max);
+        }
+        assert_eq!(list.len(), max);
         for i in 0..max {
             assert_eq!(list.at(i), Some(i as u64));
         }
         assert_eq!(list.at(max + 1), None);
     }
+
+    #[test]
+    fn dynamic_array_insert_and_remove() {
+        let mut list: DynamicArray<String> = DynamicArray::new_empty();
+        for i in 0..20 {
+            list.insert(0, i.to_string());
+        }
+        list.insert(20, "end".to_string());
+        list.insert(10, "middle".to_string());
+        assert_eq!(list.len(), 22);
+        assert_eq!(list[0], "19");
+        assert_eq!(list[10], "middle");
+        assert_eq!(list[21], "end");
+
+        assert_eq!(list.remove(10), "middle");
+        assert_eq!(list.remove(20), "end");
+        assert_eq!(list.remove(0), "19");
+        let expected: Vec<String> = (0..19).rev().map(|i| i.to_string()).collect();
+        assert_eq!(*list, expected[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index (is 2) should be <= len (is 1)")]
+    fn dynamic_array_insert_out_of_bounds() {
+        let mut list = DynamicArray::new_empty();
+        list.append(1);
+        list.insert(2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "removal index (is 1) should be < len (is 1)")]
+    fn dynamic_array_remove_out_of_bounds() {
+        let mut list = DynamicArray::new_empty();
+        list.append(1);
+        list.remove(1);
+    }
+
+    #[test]
+    fn dynamic_array_pop_truncate_clear() {
+        let mut list: DynamicArray<u32> = (0..15).collect();
+        assert_eq!(list.pop(), Some(14));
+        list.truncate(20);
+        assert_eq!(list.len(), 14);
+        list.truncate(5);
+        assert_eq!(*list, [0, 1, 2, 3, 4]);
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.pop(), None);
+        list.append(7);
+        assert_eq!(*list, [7]);
+    }
+
+    #[test]
+    fn dynamic_array_drops_every_element_once() {
+        let counter = Rc::new(());
+        let mut list = DynamicArray::new_empty();
+        for _ in 0..30 {
+            list.append(Rc::clone(&counter));
+        }
+        list.truncate(20);
+        list.remove(3);
+        drop(list.pop());
+        assert_eq!(Rc::strong_count(&counter), 19);
+
+        let mut iter = list.clone().into_iter();
+        iter.next();
+        iter.next_back();
+        assert_eq!(Rc::strong_count(&counter), 19 + 16);
+        drop(iter);
+        drop(list);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn dynamic_array_get_and_index_mut() {
+        let mut list: DynamicArray<i32> = (1..=5).collect();
+        assert_eq!(list.get(4), Some(&5));
+        assert_eq!(list.get(5), None);
+        *list.get_mut(0).unwrap() = 10;
+        list[1] += 10;
+        list[2..].iter_mut().for_each(|v| *v *= -1);
+        assert_eq!(*list, [10, 12, -3, -4, -5]);
+        assert_eq!(list[1..3], [12, -3]);
+
+        list.sort();
+        assert_eq!(list.first(), Some(&-5));
+        assert!(list.contains(&12));
+    }
+
+    #[test]
+    fn dynamic_array_iterators() {
+        let mut list: DynamicArray<i32> = (1..=4).collect();
+        for v in &mut list {
+            *v *= 2;
+        }
+        let sum: i32 = (&list).into_iter().sum();
+        assert_eq!(sum, 20);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [8, 6, 4, 2]);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(8));
+        assert_eq!(iter.collect::<Vec<_>>(), [2, 4, 6]);
+    }
+
+    #[test]
+    fn dynamic_array_without_clone() {
+        struct Token(u32);
+
+        let mut list = DynamicArray::new_empty();
+        for i in 0..100 {
+            list.append(Token(i));
+        }
+        list.insert(0, Token(1000));
+        assert_eq!(list.remove(50).0, 49);
+        assert_eq!(list.iter().map(|t| t.0).max(), Some(1000));
+        assert_eq!(list.into_iter().next().map(|t| t.0), Some(1000));
+    }
+
+    #[test]
+    fn dynamic_array_grow_moves_boxed_values() {
+        let mut list = DynamicArray::new_empty();
+        for i in 0..200 {
+            list.append(Box::new(i));
+        }
+        assert!(list.iter().enumerate().all(|(i, b)| **b == i));
+        let mut iter = list.into_iter();
+        iter.next();
+        // the remaining boxes are freed by the iterator
+    }
+
+    #[test]
+    fn dynamic_array_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DynamicArray<String>>();
+        assert_send_sync::<IntoIter<String>>();
+    }
+
+    #[test]
+    fn dynamic_array_growth_policy() {
+        fn capacities<G: GrowthPolicy>(mut list: DynamicArray<u8, G>) -> Vec<usize> {
+            let mut seen = vec![list.capacity()];
+            for i in 0..40 {
+                list.append(i);
+                if list.capacity() != *seen.last().unwrap() {
+                    seen.push(list.capacity());
+                }
+            }
+            seen
+        }
+
+        assert_eq!(capacities(DynamicArray::new_empty()), [0, 10, 15, 22, 33, 49]);
+        assert_eq!(capacities(DynamicArray::with_policy(Doubling)), [0, 1, 2, 4, 8, 16, 32, 64]);
+        assert_eq!(capacities(DynamicArray::with_policy(FixedStep(16))), [0, 16, 32, 48]);
+        assert_eq!(capacities(DynamicArray::with_policy(Exact)).len(), 41);
+        assert_eq!(capacities(DynamicArray::with_capacity_and_policy(40, Exact)), [40]);
+    }
+
+    #[test]
+    fn dynamic_array_with_capacity_and_reserve() {
+        let mut list = DynamicArray::with_capacity(100);
+        assert_eq!(list.capacity(), 100);
+        list.extend(0..100);
+        assert_eq!(list.capacity(), 100);
+
+        list.reserve(0);
+        assert_eq!(list.capacity(), 100);
+        list.reserve(1);
+        assert_eq!(list.capacity(), 150);
+        list.reserve(500);
+        assert_eq!(list.capacity(), 600);
+
+        let mut exact = DynamicArray::with_policy(Exact);
+        exact.append(1);
+        exact.reserve(10);
+        assert_eq!(exact.capacity(), 11);
+        assert_eq!(*exact.policy(), Exact);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn dynamic_array_reserve_overflow() {
+        let mut list = DynamicArray::new_empty();
+        list.append(1_u64);
+        list.reserve(usize::MAX);
+    }
+
+    #[test]
+    fn dynamic_array_shrink_to_fit() {
+        let mut list: DynamicArray<String> = (0..100).map(|i| i.to_string()).collect();
+        list.truncate(10);
+        list.shrink_to_fit();
+        assert_eq!(list.capacity(), 10);
+        assert_eq!(list[9], "9");
+        list.append("10".to_string());
+        assert_eq!(list.len(), 11);
+
+        list.clear();
+        list.shrink_to_fit();
+        assert_eq!(list.capacity(), 0);
+        list.append("again".to_string());
+        assert_eq!(*list, ["again"]);
+    }
+
+    #[test]
+    fn dynamic_array_debug() {
+        let list: DynamicArray<&str> = ["a", "b"].iter().copied().collect();
+        assert_eq!(format!("{:?}", list), r#"["a", "b"]"#);
+        assert_eq!(format!("{:?}", DynamicArray::<u8>::new_empty()), "[]");
+    }
+
+    #[test]
+    fn dynamic_array_equality() {
+        let a: DynamicArray<i32> = (1..=3).collect();
+        let mut b = DynamicArray::with_capacity_and_policy(100, Doubling);
+        b.extend(1..=3);
+        assert_eq!(a, b);
+        assert_eq!(a, [1, 2, 3]);
+        assert_eq!(a, vec![1, 2, 3]);
+        assert_eq!(a, a.clone());
+
+        b.append(4);
+        assert_ne!(a, b);
+        b.pop();
+        b[0] = 0;
+        assert_ne!(a, b);
+
+        let strings: DynamicArray<String> = ["x".to_string()].into_iter().collect();
+        assert_eq!(strings, ["x"]);
+    }
+
+    #[test]
+    fn dynamic_array_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+
+        fn hash_of<H: Hash>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a: DynamicArray<u8> = (0..10).collect();
+        let b: DynamicArray<u8, Exact> = (0..10).collect();
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_eq!(hash_of(&a), hash_of(&(0..10).collect::<Vec<u8>>()));
+
+        let mut set = HashSet::new();
+        set.insert(a.clone());
+        assert!(set.contains(&a));
+        assert!(!set.insert(a));
+    }
+
+    #[test]
+    fn dynamic_array_zero_sized_elements() {
+        let mut list = DynamicArray::new_empty();
+        for _ in 0..100 {
+            list.append(());
+        }
+        list.insert(50, ());
+        assert_eq!(list.remove(0), ());
+        assert_eq!(list.len(), 100);
+        assert_eq!(list.capacity(), usize::MAX);
+        list.shrink_to_fit();
+        assert_eq!(list.capacity(), usize::MAX);
+        assert_eq!(list.into_iter().count(), 100);
+    }
 }
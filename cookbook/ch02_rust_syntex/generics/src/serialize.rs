@@ -0,0 +1,89 @@
+//!
+//! Serde support, enabled with the `serde` feature. Arrays are written as
+//! plain sequences, so they are interchangeable with `Vec`s and slices.
+//!
+
+use std::cmp;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{DynamicArray, GrowthPolicy};
+
+///
+/// Upper bound for trusting a sequence's size hint, so a corrupt length
+/// prefix can't make us allocate huge amounts up front.
+///
+const MAX_PREALLOCATION: usize = 4096;
+
+impl<T, G> Serialize for DynamicArray<T, G> where T: Serialize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T, G> Deserialize<'de> for DynamicArray<T, G> where T: Deserialize<'de>, G: GrowthPolicy + Default {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(ArrayVisitor(PhantomData))
+    }
+}
+
+struct ArrayVisitor<T, G>(PhantomData<(T, G)>);
+impl<'de, T, G> Visitor<'de> for ArrayVisitor<T, G> where T: Deserialize<'de>, G: GrowthPolicy + Default {
+    type Value = DynamicArray<T, G>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let capacity = cmp::min(seq.size_hint().unwrap_or(0), MAX_PREALLOCATION);
+        let mut array = DynamicArray::with_capacity_and_policy(capacity, G::default());
+        while let Some(value) = seq.next_element()? {
+            array.append(value);
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DynamicArray, Exact};
+
+    #[test]
+    fn dynamic_array_json_round_trip() {
+        let list: DynamicArray<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, r#"["a","b","c"]"#);
+
+        let back: DynamicArray<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, list);
+        let as_vec: Vec<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(list, as_vec);
+    }
+
+    #[test]
+    fn dynamic_array_bincode_round_trip() {
+        let list: DynamicArray<DynamicArray<u32>> = (0..5).map(|i| (0..i).collect()).collect();
+        let bytes = bincode::serialize(&list).unwrap();
+        let as_vecs: Vec<Vec<u32>> = (0..5).map(|i| (0..i).collect()).collect();
+        assert_eq!(bytes, bincode::serialize(&as_vecs).unwrap());
+
+        let back: DynamicArray<DynamicArray<u32>, Exact> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(back.len(), 5);
+        assert_eq!(back[4], [0, 1, 2, 3]);
+        assert_eq!(back.capacity(), 5);
+    }
+
+    #[test]
+    fn dynamic_array_deserialize_errors() {
+        assert!(serde_json::from_str::<DynamicArray<u8>>(r#"{"a": 1}"#).is_err());
+        assert!(serde_json::from_str::<DynamicArray<u8>>("[1, 300]").is_err());
+        // a huge length prefix fails on the missing data, not the allocation
+        let mut bytes = bincode::serialize(&u64::MAX).unwrap();
+        bytes.extend([1, 2, 3]);
+        assert!(bincode::deserialize::<DynamicArray<u8>>(&bytes).is_err());
+    }
+}
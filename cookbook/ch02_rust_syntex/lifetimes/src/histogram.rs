@@ -0,0 +1,207 @@
+use crate::{NanPolicy, Numeric, StatisticsError, StatisticsToolkit};
+
+///
+/// The most bins a histogram may have, so that a huge `Bins::Count` or a
+/// tiny `Bins::Width` can't exhaust memory
+///
+pub const MAX_BINS: usize = 1 << 20;
+
+///
+/// How a histogram's range is divided into bins
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bins {
+    /// This many bins of equal width
+    Count(usize),
+    /// Bins of this width, starting at the smallest value
+    Width(f64),
+    /// `log2(n) + 1` bins, which suits roughly normal data
+    Sturges,
+    /// `sqrt(n)` bins
+    Sqrt,
+    /// Bins `2 * IQR / n^(1/3)` wide, robust against outliers. Falls back
+    /// to `Sturges` if the IQR is 0.
+    FreedmanDiaconis,
+}
+
+///
+/// Counts of values in consecutive bins. Bin `i` covers
+/// `edges[i]..edges[i + 1]`, the last bin includes its upper edge.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub edges: Vec<f64>,
+    pub counts: Vec<usize>,
+}
+impl Histogram {
+    ///
+    /// The bins as `(lower edge, upper edge, count)`
+    ///
+    pub fn bins(&self) -> impl Iterator<Item = (f64, f64, usize)> + '_ {
+        self.edges
+            .windows(2)
+            .zip(&self.counts)
+            .map(|(edges, count)| (edges[0], edges[1], *count))
+    }
+    ///
+    /// The number of values counted
+    ///
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+impl<'a, T> StatisticsToolkit<'a, T> where T: Numeric {
+    ///
+    /// Counts the values in bins of equal width spanning all values.
+    /// NaN values are an error unless the NaN policy omits them, and
+    /// infinite values are always an error.
+    ///
+    pub fn histogram(&self, bins: Bins) -> Result<Histogram, StatisticsError> {
+        self.require("histogram", 1)?;
+        let values: Vec<f64> = self.values().collect();
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(StatisticsError::NonFiniteValue);
+        }
+        let mut min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let mut max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if min == max {
+            // like numpy, give a single value some room
+            min -= 0.5;
+            max += 0.5;
+        }
+        let range = max - min;
+        let n = values.len() as f64;
+
+        // as a float, so a tiny width can't overflow the count
+        let count = match bins {
+            Bins::Count(count) => count as f64,
+            Bins::Width(width) if width > 0.0 && width.is_finite() => (range / width).ceil().max(1.0),
+            Bins::Width(_) => 0.0,
+            Bins::Sturges => n.log2().ceil() + 1.0,
+            Bins::Sqrt => n.sqrt().ceil(),
+            Bins::FreedmanDiaconis => {
+                let nd = StatisticsToolkit { base: &values, nan_policy: NanPolicy::Propagate };
+                let width = 2.0 * nd.iqr()? / n.cbrt();
+                if width > 0.0 {
+                    (range / width).ceil()
+                } else {
+                    n.log2().ceil() + 1.0
+                }
+            }
+        };
+        if count < 1.0 {
+            return Err(StatisticsError::InvalidBins);
+        }
+        if count > MAX_BINS as f64 {
+            return Err(StatisticsError::TooManyBins { requested: count as usize, max: MAX_BINS });
+        }
+        let count = count as usize;
+        let width = match bins {
+            Bins::Width(width) => width,
+            _ => range / count as f64,
+        };
+
+        let edges: Vec<f64> = (0..=count).map(|i| min + i as f64 * width).collect();
+        let mut counts = vec![0; count];
+        for v in values {
+            let mut bin = (((v - min) / width) as usize).min(count - 1);
+            // rounding may put values next to an edge on the wrong side
+            if v < edges[bin] {
+                bin -= 1;
+            } else if bin + 1 < count && v >= edges[bin + 1] {
+                bin += 1;
+            }
+            counts[bin] += 1;
+        }
+        Ok(Histogram { edges, counts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn statisticstoolkit_histogram_count() {
+        let a_sample = vec![1.0, 2.0, 2.5, 3.0, 4.0, 5.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        let histogram = nd.histogram(Bins::Count(4)).unwrap();
+        assert_eq!(histogram.edges, [1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(histogram.counts, [1, 2, 1, 2]);
+        assert_eq!(histogram.total(), 6);
+        assert_eq!(histogram.bins().next(), Some((1.0, 2.0, 1)));
+    }
+
+    #[test]
+    fn statisticstoolkit_histogram_width() {
+        let a_sample = vec![0.0, 0.1, 0.2, 0.3, 0.7];
+        let nd = StatisticsToolkit::new(&a_sample);
+        let histogram = nd.histogram(Bins::Width(0.25)).unwrap();
+        assert_eq!(histogram.edges, [0.0, 0.25, 0.5, 0.75]);
+        assert_eq!(histogram.counts, [3, 1, 1]);
+
+        assert_eq!(nd.histogram(Bins::Width(0.0)), Err(StatisticsError::InvalidBins));
+        assert_eq!(nd.histogram(Bins::Width(f64::NAN)), Err(StatisticsError::InvalidBins));
+        assert_eq!(nd.histogram(Bins::Count(0)), Err(StatisticsError::InvalidBins));
+        assert_eq!(nd.histogram(Bins::Width(-0.25)), Err(StatisticsError::InvalidBins));
+        assert_eq!(nd.histogram(Bins::Width(f64::INFINITY)), Err(StatisticsError::InvalidBins));
+    }
+
+    #[test]
+    fn statisticstoolkit_histogram_too_many_bins() {
+        let a_sample = vec![0.0, 1.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(
+            nd.histogram(Bins::Count(usize::MAX)),
+            Err(StatisticsError::TooManyBins { requested: usize::MAX, max: MAX_BINS })
+        );
+        assert_eq!(
+            nd.histogram(Bins::Width(1e-300)),
+            Err(StatisticsError::TooManyBins { requested: usize::MAX, max: MAX_BINS })
+        );
+        assert_eq!(
+            nd.histogram(Bins::Width(0.5 / MAX_BINS as f64)).unwrap_err().to_string(),
+            "2097152 histogram bins requested, at most 1048576 are supported"
+        );
+        assert_eq!(nd.histogram(Bins::Count(MAX_BINS)).unwrap().counts.len(), MAX_BINS);
+    }
+
+    #[test]
+    fn statisticstoolkit_histogram_automatic_bins() {
+        let a_sample: Vec<f64> = (0..100).map(|i| (i * i) as f64 / 100.0).collect();
+        let nd = StatisticsToolkit::new(&a_sample);
+        for (bins, count) in [(Bins::Sturges, 8), (Bins::Sqrt, 10), (Bins::FreedmanDiaconis, 5)] {
+            let histogram = nd.histogram(bins).unwrap();
+            assert_eq!(histogram.counts.len(), count, "{:?}", bins);
+            assert_eq!(histogram.total(), 100);
+            assert_eq!(histogram.edges.last(), Some(&98.01));
+        }
+
+        // a single value gets one bin around it
+        let a_sample = vec![2.0, 2.0, 2.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        let histogram = nd.histogram(Bins::FreedmanDiaconis).unwrap();
+        assert_eq!(histogram.edges.first(), Some(&1.5));
+        assert_eq!(histogram.total(), 3);
+    }
+
+    #[test]
+    fn statisticstoolkit_histogram_non_finite() {
+        let a_sample = vec![1.0, f64::NAN, 2.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.histogram(Bins::Count(2)), Err(StatisticsError::NonFiniteValue));
+        let histogram = nd.with_nan_policy(NanPolicy::Omit).histogram(Bins::Count(2)).unwrap();
+        assert_eq!(histogram.counts, [1, 1]);
+
+        let a_sample = vec![1.0, f64::INFINITY, 2.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.histogram(Bins::Sqrt), Err(StatisticsError::NonFiniteValue));
+
+        let a_sample = vec![f64::NAN];
+        let nd = StatisticsToolkit::new(&a_sample).with_nan_policy(NanPolicy::Omit);
+        assert!(matches!(nd.histogram(Bins::Sqrt), Err(StatisticsError::TooFewValues { .. })));
+        let histogram = StatisticsToolkit::new(&[1_u32, 2, 2, 3]).histogram(Bins::Count(2)).unwrap();
+        assert_eq!(histogram.counts, [1, 3]);
+    }
+}
@@ -1,57 +1,228 @@
+mod histogram;
+mod numeric;
+mod paired;
+mod quantiles;
+mod robust;
+mod streaming;
+pub use histogram::{Bins, Histogram, MAX_BINS};
+pub use numeric::Numeric;
+pub use paired::{LinearRegression, PairedToolkit};
+pub use quantiles::Interpolation;
+pub use robust::MAD_NORMAL_SCALE;
+pub use streaming::OnlineStatistics;
+
+use std::error::Error;
+use std::fmt;
+
+///
+/// What the toolkit does with NaN values in its slice
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Any NaN makes every statistic NaN (and histograms fail)
+    #[default]
+    Propagate,
+    /// NaN values are skipped as if they weren't in the slice
+    Omit,
+}
+
+///
+/// A statistic couldn't be computed with the given arguments
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatisticsError {
+    /// The statistic needs more values than there are (after omitting NaNs)
+    TooFewValues {
+        statistic: &'static str,
+        required: usize,
+        actual: usize,
+    },
+    /// Paired series must have the same length
+    LengthMismatch { x: usize, y: usize },
+    /// Quantiles must be within 0..=1 (percentiles within 0..=100)
+    InvalidQuantile(f64),
+    /// Trimming and winsorizing need a proportion within 0..0.5
+    InvalidProportion(f64),
+    /// Histograms need at least one bin of a positive, finite width
+    InvalidBins,
+    /// The bins asked for would be more than `MAX_BINS`
+    TooManyBins { requested: usize, max: usize },
+    /// The values contain NaN or infinity where only finite values work
+    NonFiniteValue,
+}
+impl fmt::Display for StatisticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatisticsError::TooFewValues { statistic, required, actual } => write!(
+                f,
+                "{} needs at least {} value{}, got {}",
+                statistic,
+                required,
+                if *required == 1 { "" } else { "s" },
+                actual
+            ),
+            StatisticsError::LengthMismatch { x, y } => {
+                write!(f, "paired series differ in length: {} and {}", x, y)
+            }
+            StatisticsError::InvalidQuantile(q) => write!(f, "quantile {} is outside of 0..=1", q),
+            StatisticsError::InvalidProportion(p) => write!(f, "proportion {} is outside of 0..0.5", p),
+            StatisticsError::InvalidBins => write!(f, "histogram bins must have a positive, finite width"),
+            StatisticsError::TooManyBins { requested, max } => {
+                write!(f, "{} histogram bins requested, at most {} are supported", requested, max)
+            }
+            StatisticsError::NonFiniteValue => write!(f, "values must be finite"),
+        }
+    }
+}
+impl Error for StatisticsError {}
+
+///
+/// Our almost generic statistics toolkit, for slices of floats or integers.
 ///
-/// Our almost generic statistics toolkit
+/// Every statistic checks that there are enough values for it, e.g. one
+/// for the mean and two for the sample variance, and returns a
+/// `StatisticsError::TooFewValues` otherwise.
 /// 
-pub struct StatisticsToolkit<'a> {
-    base: &'a [f64],
+pub struct StatisticsToolkit<'a, T = f64> {
+    base: &'a [T],
+    nan_policy: NanPolicy,
 }
-impl<'a> StatisticsToolkit<'a> {
+impl<'a, T> StatisticsToolkit<'a, T> where T: Numeric {
     ///
-    /// Create a new instance if the slice is larger than 2 elements
+    /// Create a new instance
     /// 
-    pub fn new(base: &'a [f64]) -> Option<StatisticsToolkit> {
-        if base.len() < 3 {
-            None
+    pub fn new(base: &'a [T]) -> StatisticsToolkit<'a, T> {
+        StatisticsToolkit { base, nan_policy: NanPolicy::default() }
+    }
+    ///
+    /// Sets how NaN values are treated, `NanPolicy::Propagate` by default
+    ///
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> StatisticsToolkit<'a, T> {
+        self.nan_policy = nan_policy;
+        self
+    }
+    ///
+    /// The values the statistics are computed from
+    ///
+    fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        let omit = self.nan_policy == NanPolicy::Omit;
+        self.base.iter().map(|v| v.to_f64()).filter(move |v| !(omit && v.is_nan()))
+    }
+    ///
+    /// The number of values if there are at least `required`
+    ///
+    fn require(&self, statistic: &'static str, required: usize) -> Result<usize, StatisticsError> {
+        let actual = self.values().count();
+        if actual < required {
+            Err(StatisticsError::TooFewValues { statistic, required, actual })
         } else {
-            Some(StatisticsToolkit { base: base })
+            Ok(actual)
+        }
+    }
+    ///
+    /// The values in ascending order, or `None` if NaNs propagate and
+    /// there are some
+    ///
+    fn sorted(&self) -> Option<Vec<f64>> {
+        let mut sorted: Vec<f64> = self.values().collect();
+        if self.nan_policy == NanPolicy::Propagate && sorted.iter().any(|v| v.is_nan()) {
+            return None;
         }
+        sorted.sort_by(f64::total_cmp);
+        Some(sorted)
     }
     ///
-    /// Computes the variance
+    /// The mean of `(x - mean)^k` for the central moment `k`
+    ///
+    fn central_moment(&self, mean: f64, k: i32) -> f64 {
+        let sum: f64 = self.values().map(|i| (i - mean).powi(k)).sum();
+        sum / self.values().count() as f64
+    }
+    ///
+    /// Computes the population variance, dividing by n
     /// 
-    pub fn var(&self) -> f64 {
-        let mean = self.mean();
-        let ssq: f64 = self.base.iter().map(|i| (i - mean).powi(2)).sum();
-    
-        ssq / self.base.len() as f64
+    pub fn var(&self) -> Result<f64, StatisticsError> {
+        self.require("variance", 1)?;
+        Ok(self.central_moment(self.mean()?, 2))
     }
     ///
-    /// Computes the standard deviation
+    /// Computes the population standard deviation
     /// 
-    pub fn std(&self) -> f64 {
-        self.var().sqrt()
+    pub fn std(&self) -> Result<f64, StatisticsError> {
+        self.var().map(f64::sqrt)
+    }
+    ///
+    /// Computes the sample variance, dividing by n - 1 so it doesn't
+    /// underestimate the variance of the population the sample is from
+    ///
+    pub fn sample_var(&self) -> Result<f64, StatisticsError> {
+        let n = self.require("sample variance", 2)? as f64;
+        Ok(self.central_moment(self.mean()?, 2) * n / (n - 1.0))
+    }
+    ///
+    /// Computes the sample standard deviation
+    ///
+    pub fn sample_std(&self) -> Result<f64, StatisticsError> {
+        self.sample_var().map(f64::sqrt)
+    }
+    ///
+    /// Computes the population skewness `m3 / m2^1.5`, which is NaN if
+    /// all values are equal
+    ///
+    pub fn skewness(&self) -> Result<f64, StatisticsError> {
+        self.require("skewness", 1)?;
+        let mean = self.mean()?;
+        Ok(self.central_moment(mean, 3) / self.central_moment(mean, 2).powf(1.5))
+    }
+    ///
+    /// Computes the adjusted Fisher-Pearson sample skewness
+    ///
+    pub fn sample_skewness(&self) -> Result<f64, StatisticsError> {
+        let n = self.require("sample skewness", 3)? as f64;
+        Ok(self.skewness()? * (n * (n - 1.0)).sqrt() / (n - 2.0))
+    }
+    ///
+    /// Computes the population excess kurtosis `m4 / m2^2 - 3`, which is 0
+    /// for a normal distribution and NaN if all values are equal
+    ///
+    pub fn kurtosis(&self) -> Result<f64, StatisticsError> {
+        self.require("kurtosis", 1)?;
+        let mean = self.mean()?;
+        Ok(self.central_moment(mean, 4) / self.central_moment(mean, 2).powi(2) - 3.0)
+    }
+    ///
+    /// Computes the sample excess kurtosis, corrected for the bias of
+    /// small samples
+    ///
+    pub fn sample_kurtosis(&self) -> Result<f64, StatisticsError> {
+        let n = self.require("sample kurtosis", 4)? as f64;
+        let g2 = self.kurtosis()?;
+        Ok(((n + 1.0) * g2 + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0)))
     }
     ///
     /// Computes the arithmetic mean
     /// 
-    pub fn mean(&self) -> f64 {
-        let sum: f64 = self.base.iter().sum();
+    pub fn mean(&self) -> Result<f64, StatisticsError> {
+        let n = self.require("mean", 1)?;
+        let sum: f64 = self.values().sum();
 
-        sum / self.base.len() as f64
+        Ok(sum / n as f64)
     }
     ///
-    /// Computes the median, but clones the base slice for this.
+    /// Computes the median, but sorts a copy of the base slice for this.
     /// 
-    pub fn median(&self) -> f64 {
-        let mut clone = self.base.to_vec();
+    pub fn median(&self) -> Result<f64, StatisticsError> {
+        self.require("median", 1)?;
+        let sorted = match self.sorted() {
+            Some(sorted) => sorted,
+            None => return Ok(f64::NAN),
+        };
 
-        // .sort() is not implemented for floats
-        clone.sort_by(|a, b| a.partial_cmp(b).unwrap()); 
-
-        let m = clone.len() / 2;
-        if clone.len() % 2 == 0 {
-            clone[m]
+        let m = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            Ok((sorted[m] + sorted[m - 1]) / 2.0)
         } else {
-            (clone[m] + clone[m - 1]) / 2.0
+            Ok(sorted[m])
         }
     }
 }
@@ -59,10 +230,10 @@ impl<'a> StatisticsToolkit<'a> {
 ///
 /// Compute the arithmetic mean
 /// 
-pub fn mean<'a>(numbers: &'a [f32]) -> Option<f32> {
-    if numbers.len() > 0 {
-        let sum: f32 = numbers.iter().sum();
-        Some(sum / numbers.len() as f32)
+pub fn mean<T: Numeric>(numbers: &[T]) -> Option<f64> {
+    if !numbers.is_empty() {
+        let sum: f64 = numbers.iter().map(|n| n.to_f64()).sum();
+        Some(sum / numbers.len() as f64)
     } else {
         None
     }
@@ -85,55 +256,149 @@ mod tests {
 
     #[test]
     fn mean_tests() {
-        assert_eq!(mean(&vec![1.0, 2.0, 3.0]), Some(2.0));
-        assert_eq!(mean(&vec![]), None);
-        assert_eq!(mean(&vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]), Some(0.0));
+        assert_eq!(mean(&[1.0, 2.0, 3.0]), Some(2.0));
+        assert_eq!(mean::<f32>(&[]), None);
+        assert_eq!(mean(&[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]), Some(0.0));
+        assert_eq!(mean(&[1_f32, 2.5]), Some(1.75));
+        assert_eq!(mean(&[1_u8, 2, 4]), Some(2.3333333333333335));
     }
 
     #[test]
     fn statisticstoolkit_new() {
-        assert!(StatisticsToolkit::new(&vec![]).is_none());
-        assert!(StatisticsToolkit::new(&vec![2.0, 2.0]).is_none());
-        assert!(StatisticsToolkit::new(&vec![1.0, 2.0, 1.0]).is_some());
-        assert!(StatisticsToolkit::new(&vec![2.0, 1.0, 2.0]).is_some());
+        assert_eq!(
+            StatisticsToolkit::<f64>::new(&[]).mean(),
+            Err(StatisticsError::TooFewValues { statistic: "mean", required: 1, actual: 0 })
+        );
+        assert_eq!(StatisticsToolkit::new(&[2.0]).median(), Ok(2.0));
+        assert_eq!(StatisticsToolkit::new(&[2.0, 2.0]).var(), Ok(0.0));
+        assert!(StatisticsToolkit::new(&[2.0]).sample_var().is_err());
+        assert!(StatisticsToolkit::new(&[1.0, 2.0, 1.0]).sample_kurtosis().is_err());
+        assert!(StatisticsToolkit::new(&[2.0, 1.0, 2.0, 1.0]).sample_kurtosis().is_ok());
     }
 
     #[test]
     fn statisticstoolkit_statistics() {
         let a_sample = vec![1.0, 2.0, 1.0];
-        let nd = StatisticsToolkit::new(&a_sample).unwrap();
-        assert_eq!(nd.var(), 0.2222222222222222);
-        assert_eq!(nd.std(), 0.4714045207910317);
-        assert_eq!(nd.mean(), 1.3333333333333333);
-        assert_eq!(nd.median(), 1.0);
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.var(), Ok(0.2222222222222222));
+        assert_eq!(nd.std(), Ok(0.4714045207910317));
+        assert_eq!(nd.mean(), Ok(1.3333333333333333));
+        assert_eq!(nd.median(), Ok(1.0));
 
         let a_sample = vec![1.0, 1.0, 1.0];
-        let nd = StatisticsToolkit::new(&a_sample).unwrap();
-        assert_eq!(nd.var(), 0.0);
-        assert_eq!(nd.std(), 0.0);
-        assert_eq!(nd.mean(), 1.0);
-        assert_eq!(nd.median(), 1.0);
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.var(), Ok(0.0));
+        assert_eq!(nd.std(), Ok(0.0));
+        assert_eq!(nd.mean(), Ok(1.0));
+        assert_eq!(nd.median(), Ok(1.0));
 
         let a_sample = numpy_normal_distribution();
-        let nd = StatisticsToolkit::new(&a_sample).unwrap();
-        assert_eq!(nd.var(), 8.580276516670548);
-        assert_eq!(nd.std(), 2.9292109034124785);
-        assert_eq!(nd.mean(), 42.36319998250001);
-        assert_eq!(nd.median(), 42.61511209);
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.var(), Ok(8.580276516670548));
+        assert_eq!(nd.std(), Ok(2.9292109034124785));
+        assert_eq!(nd.mean(), Ok(42.36319998250001));
+        assert_eq!(nd.median(), Ok(42.433051355));
 
         let a_sample = vec![1.0, 1.0, 5.0];
-        let nd = StatisticsToolkit::new(&a_sample).unwrap();
-        assert_eq!(nd.var(), 3.555555555555556);
-        assert_eq!(nd.std(), 1.8856180831641267);
-        assert_eq!(nd.mean(), 2.3333333333333335);
-        assert_eq!(nd.median(), 1.0);
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.var(), Ok(3.555555555555556));
+        assert_eq!(nd.std(), Ok(1.8856180831641267));
+        assert_eq!(nd.mean(), Ok(2.3333333333333335));
+        assert_eq!(nd.median(), Ok(1.0));
 
         // median with even collection length
         let a_sample = vec![1.0, 2.0, 3.0, 4.0] ;
-        let nd = StatisticsToolkit::new(&a_sample).unwrap();
-        assert_eq!(nd.var(), 1.25);
-        assert_eq!(nd.std(), 1.118033988749895);
-        assert_eq!(nd.mean(), 2.5);
-        assert_eq!(nd.median(), 3.0);
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.var(), Ok(1.25));
+        assert_eq!(nd.std(), Ok(1.118033988749895));
+        assert_eq!(nd.mean(), Ok(2.5));
+        assert_eq!(nd.median(), Ok(2.5));
+    }
+
+    #[test]
+    fn statisticstoolkit_median() {
+        let a_sample = vec![5.0, 1.0, 4.0, 2.0, 3.0];
+        assert_eq!(StatisticsToolkit::new(&a_sample).median(), Ok(3.0));
+        let a_sample = vec![6.0, 5.0, 1.0, 4.0, 2.0, 3.0];
+        assert_eq!(StatisticsToolkit::new(&a_sample).median(), Ok(3.5));
+        let a_sample = vec![-0.0, f64::INFINITY, f64::NEG_INFINITY];
+        assert_eq!(StatisticsToolkit::new(&a_sample).median(), Ok(0.0));
+    }
+
+    #[test]
+    fn statisticstoolkit_sample_statistics() {
+        let close = |a: Result<f64, StatisticsError>, b: f64| {
+            let a = a.unwrap();
+            assert!((a - b).abs() < 1e-12, "{} != {}", a, b);
+        };
+        // the formulas of scipy.stats' skew and kurtosis with bias=True and bias=False
+        let a_sample = vec![2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        close(nd.var(), 13.859375);
+        close(nd.sample_var(), 15.839285714285714);
+        close(nd.sample_std(), 15.839285714285714_f64.sqrt());
+        close(nd.skewness(), 0.2650554122698573);
+        close(nd.sample_skewness(), 0.33058218040797466);
+        close(nd.kurtosis(), -1.6660010752838508);
+        close(nd.sample_kurtosis(), -2.098602258096087);
+
+        let a_sample = vec![3.0, 3.0, 3.0, 3.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert!(nd.skewness().unwrap().is_nan());
+        assert!(nd.kurtosis().unwrap().is_nan());
+    }
+
+    #[test]
+    fn statisticstoolkit_generic_input() {
+        let ints = vec![2_i32, 8, 0, 4, 1, 9, 9, 0];
+        let floats: Vec<f32> = ints.iter().map(|i| *i as f32).collect();
+        let expected = StatisticsToolkit::new(&[2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0]).sample_var();
+
+        assert_eq!(StatisticsToolkit::new(&ints).sample_var(), expected);
+        assert_eq!(StatisticsToolkit::new(&floats).sample_var(), expected);
+        assert_eq!(StatisticsToolkit::new(&[1_u64, 2, 3, 4]).median(), Ok(2.5));
+        assert_eq!(StatisticsToolkit::new(&[250_u8, 251]).mean(), Ok(250.5));
+
+        let with_nan = vec![1_f32, f32::NAN, 3.0];
+        let nd = StatisticsToolkit::new(&with_nan).with_nan_policy(NanPolicy::Omit);
+        assert_eq!(nd.mean(), Ok(2.0));
+    }
+
+    #[test]
+    fn statisticstoolkit_nan_policy() {
+        let a_sample = vec![1.0, f64::NAN, 2.0, 3.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert!(nd.median().unwrap().is_nan());
+        assert!(nd.mean().unwrap().is_nan());
+        assert!(nd.var().unwrap().is_nan());
+
+        let nd = nd.with_nan_policy(NanPolicy::Omit);
+        assert_eq!(nd.median(), Ok(2.0));
+        assert_eq!(nd.mean(), Ok(2.0));
+        assert_eq!(nd.var(), Ok(0.6666666666666666));
+        assert_eq!(nd.sample_var(), Ok(1.0));
+
+        let a_sample = vec![f64::NAN; 3];
+        let nd = StatisticsToolkit::new(&a_sample).with_nan_policy(NanPolicy::Omit);
+        assert!(nd.median().is_err());
+        assert!(nd.mean().is_err());
+    }
+
+    #[test]
+    fn statistics_error_messages() {
+        assert_eq!(StatisticsError::InvalidQuantile(1.5).to_string(), "quantile 1.5 is outside of 0..=1");
+        assert_eq!(StatisticsError::NonFiniteValue.to_string(), "values must be finite");
+        assert_eq!(
+            StatisticsError::LengthMismatch { x: 3, y: 4 }.to_string(),
+            "paired series differ in length: 3 and 4"
+        );
+        assert_eq!(
+            StatisticsToolkit::new(&[1.0]).sample_var().unwrap_err().to_string(),
+            "sample variance needs at least 2 values, got 1"
+        );
+        assert_eq!(
+            StatisticsToolkit::<f64>::new(&[]).median().unwrap_err().to_string(),
+            "median needs at least 1 value, got 0"
+        );
     }
 }
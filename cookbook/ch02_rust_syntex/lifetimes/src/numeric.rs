@@ -0,0 +1,17 @@
+///
+/// Numbers the statistics can be computed from. Statistics are always
+/// computed in `f64`, so very large 64-bit integers lose precision.
+///
+pub trait Numeric: Copy {
+    fn to_f64(self) -> f64;
+}
+macro_rules! numeric {
+    ($($t:ty),*) => {
+        $(impl Numeric for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+numeric!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
@@ -0,0 +1,264 @@
+use crate::{NanPolicy, Numeric, StatisticsError};
+
+///
+/// Statistics over two series of equal length, where `x[i]` and `y[i]`
+/// were observed together, e.g. a sensor's temperature and humidity.
+///
+/// Like `StatisticsToolkit`, it is generic over the number types and
+/// checks the number of pairs each statistic needs. With
+/// `NanPolicy::Omit`, pairs with a NaN on either side are skipped.
+///
+pub struct PairedToolkit<'a, X = f64, Y = f64> {
+    x: &'a [X],
+    y: &'a [Y],
+    nan_policy: NanPolicy,
+}
+
+///
+/// A fitted line `y = slope * x + intercept`
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearRegression {
+    pub slope: f64,
+    pub intercept: f64,
+    /// The share of the variance of `y` explained by the line, 1 for a perfect fit
+    pub r_squared: f64,
+    /// `y[i] - predict(x[i])` for every pair used in the fit
+    pub residuals: Vec<f64>,
+}
+impl LinearRegression {
+    pub fn predict(&self, x: f64) -> f64 {
+        self.slope * x + self.intercept
+    }
+}
+
+impl<'a, X, Y> PairedToolkit<'a, X, Y> where X: Numeric, Y: Numeric {
+    ///
+    /// Create a new instance if both series have the same length
+    ///
+    pub fn new(x: &'a [X], y: &'a [Y]) -> Result<PairedToolkit<'a, X, Y>, StatisticsError> {
+        if x.len() != y.len() {
+            return Err(StatisticsError::LengthMismatch { x: x.len(), y: y.len() });
+        }
+        Ok(PairedToolkit { x, y, nan_policy: NanPolicy::default() })
+    }
+    ///
+    /// Sets how NaN values are treated, `NanPolicy::Propagate` by default
+    ///
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> PairedToolkit<'a, X, Y> {
+        self.nan_policy = nan_policy;
+        self
+    }
+    ///
+    /// The pairs the statistics are computed from, if there are at least
+    /// `required`
+    ///
+    fn pairs(&self, statistic: &'static str, required: usize) -> Result<Vec<(f64, f64)>, StatisticsError> {
+        let omit = self.nan_policy == NanPolicy::Omit;
+        let pairs: Vec<(f64, f64)> = self
+            .x
+            .iter()
+            .zip(self.y)
+            .map(|(x, y)| (x.to_f64(), y.to_f64()))
+            .filter(|(x, y)| !(omit && (x.is_nan() || y.is_nan())))
+            .collect();
+        if pairs.len() < required {
+            return Err(StatisticsError::TooFewValues { statistic, required, actual: pairs.len() });
+        }
+        Ok(pairs)
+    }
+    ///
+    /// Computes the population covariance, dividing by n
+    ///
+    pub fn covariance(&self) -> Result<f64, StatisticsError> {
+        let pairs = self.pairs("covariance", 1)?;
+        Ok(co_moment(&pairs) / pairs.len() as f64)
+    }
+    ///
+    /// Computes the sample covariance, dividing by n - 1
+    ///
+    pub fn sample_covariance(&self) -> Result<f64, StatisticsError> {
+        let pairs = self.pairs("sample covariance", 2)?;
+        Ok(co_moment(&pairs) / (pairs.len() - 1) as f64)
+    }
+    ///
+    /// Computes Pearson's correlation coefficient, which measures how
+    /// linear the relation is. It is NaN if either series is constant.
+    ///
+    pub fn pearson(&self) -> Result<f64, StatisticsError> {
+        Ok(pearson(&self.pairs("Pearson correlation", 2)?))
+    }
+    ///
+    /// Computes Spearman's rank correlation coefficient, which measures how
+    /// monotonic the relation is. Tied values get the average of their ranks.
+    ///
+    pub fn spearman(&self) -> Result<f64, StatisticsError> {
+        let pairs = self.pairs("Spearman correlation", 2)?;
+        if pairs.iter().any(|(x, y)| x.is_nan() || y.is_nan()) {
+            return Ok(f64::NAN);
+        }
+        let (x, y): (Vec<f64>, Vec<f64>) = pairs.into_iter().unzip();
+        let ranked: Vec<(f64, f64)> = ranks(&x).into_iter().zip(ranks(&y)).collect();
+        Ok(pearson(&ranked))
+    }
+    ///
+    /// Fits a line through the pairs with ordinary least squares. The
+    /// slope and intercept are NaN if all `x` are equal.
+    ///
+    pub fn linear_regression(&self) -> Result<LinearRegression, StatisticsError> {
+        let pairs = self.pairs("linear regression", 2)?;
+        let (mean_x, mean_y) = means(&pairs);
+        let ss_x: f64 = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let ss_y: f64 = pairs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+        let slope = co_moment(&pairs) / ss_x;
+        let intercept = mean_y - slope * mean_x;
+        let residuals: Vec<f64> = pairs.iter().map(|(x, y)| y - (slope * x + intercept)).collect();
+        let ss_res: f64 = residuals.iter().map(|r| r.powi(2)).sum();
+
+        Ok(LinearRegression {
+            slope,
+            intercept,
+            r_squared: 1.0 - ss_res / ss_y,
+            residuals,
+        })
+    }
+}
+
+fn means(pairs: &[(f64, f64)]) -> (f64, f64) {
+    let n = pairs.len() as f64;
+    let (sum_x, sum_y) = pairs.iter().fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    (sum_x / n, sum_y / n)
+}
+
+///
+/// The sum of `(x - mean x) * (y - mean y)`
+///
+fn co_moment(pairs: &[(f64, f64)]) -> f64 {
+    let (mean_x, mean_y) = means(pairs);
+    pairs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum()
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> f64 {
+    let (mean_x, mean_y) = means(pairs);
+    let ss_x: f64 = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let ss_y: f64 = pairs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    co_moment(pairs) / (ss_x * ss_y).sqrt()
+}
+
+///
+/// The 1-based ranks of `values`, averaged over ties
+///
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // positions start..end share the average of ranks start + 1..=end
+        let rank = (start + 1 + end) as f64 / 2.0;
+        for i in &order[start..end] {
+            ranks[*i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn pairedtoolkit_new() {
+        assert!(PairedToolkit::new(&[1.0, 2.0], &[1.0, 2.0]).is_ok());
+        assert_eq!(
+            PairedToolkit::new(&[1.0, 2.0], &[1.0]).err(),
+            Some(StatisticsError::LengthMismatch { x: 2, y: 1 })
+        );
+        let single = PairedToolkit::new(&[1.0], &[2.0]).unwrap();
+        assert_eq!(single.covariance(), Ok(0.0));
+        assert_eq!(
+            single.pearson(),
+            Err(StatisticsError::TooFewValues { statistic: "Pearson correlation", required: 2, actual: 1 })
+        );
+    }
+
+    #[test]
+    fn pairedtoolkit_covariance_and_pearson() {
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let y = [2.0, 4.0, 5.0, 4.0, 5.0];
+        let paired = PairedToolkit::new(&x, &y).unwrap();
+        close(paired.covariance().unwrap(), 1.2);
+        close(paired.sample_covariance().unwrap(), 1.5);
+        close(paired.pearson().unwrap(), 0.7745966692414834);
+
+        let negative = PairedToolkit::new(&x, &[10, 8, 6, 4, 2]).unwrap();
+        close(negative.pearson().unwrap(), -1.0);
+        let constant = PairedToolkit::new(&x, &[3, 3, 3, 3, 3]).unwrap();
+        assert!(constant.pearson().unwrap().is_nan());
+    }
+
+    #[test]
+    fn pairedtoolkit_spearman() {
+        // monotonic but not linear
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let y = [1.0, 8.0, 27.0, 64.0, 125.0];
+        let paired = PairedToolkit::new(&x, &y).unwrap();
+        close(paired.spearman().unwrap(), 1.0);
+        assert!(paired.pearson().unwrap() < 1.0);
+
+        // ties: Pearson of the ranks [1, 2.5, 2.5, 4, 5, 6] and [5, 1, 2.5, 2.5, 6, 4]
+        let x = [1.0, 2.0, 2.0, 3.0, 4.0, 5.0];
+        let y = [3.0, 1.0, 2.0, 2.0, 6.0, 5.0];
+        let paired = PairedToolkit::new(&x, &y).unwrap();
+        close(paired.spearman().unwrap(), 0.5441176470588235);
+        assert_eq!(ranks(&[20.0, 10.0, 20.0, 30.0]), [2.5, 1.0, 2.5, 4.0]);
+    }
+
+    #[test]
+    fn pairedtoolkit_linear_regression() {
+        let x = [1_u32, 2, 3, 4, 5];
+        let y = [2.0, 4.0, 5.0, 4.0, 5.0];
+        let fit = PairedToolkit::new(&x, &y).unwrap().linear_regression().unwrap();
+        close(fit.slope, 0.6);
+        close(fit.intercept, 2.2);
+        close(fit.r_squared, 0.6);
+        close(fit.predict(6.0), 5.8);
+        let expected = [-0.8, 0.6, 1.0, -0.6, -0.2];
+        for (r, e) in fit.residuals.iter().zip(&expected) {
+            close(*r, *e);
+        }
+
+        let perfect = PairedToolkit::new(&[0.0, 1.0, 2.0], &[1.0, 3.0, 5.0]).unwrap();
+        let fit = perfect.linear_regression().unwrap();
+        assert_eq!((fit.slope, fit.intercept, fit.r_squared), (2.0, 1.0, 1.0));
+
+        let vertical = PairedToolkit::new(&[1.0, 1.0], &[1.0, 3.0]).unwrap();
+        assert!(vertical.linear_regression().unwrap().slope.is_nan());
+    }
+
+    #[test]
+    fn pairedtoolkit_nan_policy() {
+        let x = [1.0, f64::NAN, 2.0, 3.0];
+        let y = [2.0, 5.0, f64::NAN, 6.0];
+        let paired = PairedToolkit::new(&x, &y).unwrap();
+        assert!(paired.pearson().unwrap().is_nan());
+        assert!(paired.spearman().unwrap().is_nan());
+
+        let paired = paired.with_nan_policy(NanPolicy::Omit);
+        close(paired.pearson().unwrap(), 1.0);
+        let fit = paired.linear_regression().unwrap();
+        assert_eq!(fit.residuals.len(), 2);
+        close(fit.slope, 2.0);
+    }
+}
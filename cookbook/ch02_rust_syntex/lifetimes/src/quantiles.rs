@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use crate::{Numeric, StatisticsError, StatisticsToolkit};
+
+///
+/// How a quantile is picked when it falls between two values `i < j`
+/// of the sorted data (the same choices as numpy's `percentile`)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// `i + (j - i) * fraction`
+    #[default]
+    Linear,
+    /// `i`
+    Lower,
+    /// `j`
+    Higher,
+    /// `i` or `j`, whichever is closer (ties go to the even index)
+    Nearest,
+    /// `(i + j) / 2`
+    Midpoint,
+}
+
+impl<'a, T> StatisticsToolkit<'a, T> where T: Numeric {
+    ///
+    /// Computes the `q`-quantile for `q` in `0..=1`, e.g. 0.5 for the
+    /// median, using the sorted values at positions `q * (n - 1)`.
+    ///
+    pub fn quantile(&self, q: f64, interpolation: Interpolation) -> Result<f64, StatisticsError> {
+        if !(0.0..=1.0).contains(&q) {
+            return Err(StatisticsError::InvalidQuantile(q));
+        }
+        self.require("quantile", 1)?;
+        let sorted = match self.sorted() {
+            Some(sorted) => sorted,
+            None => return Ok(f64::NAN),
+        };
+
+        let position = q * (sorted.len() - 1) as f64;
+        let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+        let fraction = position - lower as f64;
+        let (i, j) = (sorted[lower], sorted[upper]);
+
+        Ok(match interpolation {
+            // i == j would give NaN for infinities
+            _ if lower == upper => i,
+            Interpolation::Linear => i + (j - i) * fraction,
+            Interpolation::Lower => i,
+            Interpolation::Higher => j,
+            Interpolation::Nearest => {
+                let nearest = position.round_ties_even() as usize;
+                sorted[nearest]
+            }
+            Interpolation::Midpoint => (i + j) / 2.0,
+        })
+    }
+    ///
+    /// Computes the `p`-th percentile for `p` in `0..=100`
+    ///
+    pub fn percentile(&self, p: f64, interpolation: Interpolation) -> Result<f64, StatisticsError> {
+        self.quantile(p / 100.0, interpolation)
+    }
+    ///
+    /// Computes the interquartile range, the distance between the
+    /// first and third quartile (linearly interpolated)
+    ///
+    pub fn iqr(&self) -> Result<f64, StatisticsError> {
+        let q1 = self.quantile(0.25, Interpolation::Linear)?;
+        let q3 = self.quantile(0.75, Interpolation::Linear)?;
+        Ok(q3 - q1)
+    }
+    ///
+    /// Finds the most frequent value, the smallest one if there are
+    /// several. Only exactly equal values count as the same.
+    ///
+    pub fn mode(&self) -> Result<f64, StatisticsError> {
+        self.require("mode", 1)?;
+        let sorted = match self.sorted() {
+            Some(sorted) => sorted,
+            None => return Ok(f64::NAN),
+        };
+
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for v in &sorted {
+            // 0.0 == -0.0, but their bits differ
+            *counts.entry((v + 0.0).to_bits()).or_default() += 1;
+        }
+        let max = counts.values().copied().max().unwrap_or_default();
+        Ok(sorted
+            .into_iter()
+            .find(|v| counts[&(v + 0.0).to_bits()] == max)
+            .unwrap_or(f64::NAN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn statisticstoolkit_quantile_interpolation() {
+        let a_sample = vec![4.0, 1.0, 3.0, 2.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        // position 0.4 * 3 = 1.2, between 2.0 and 3.0
+        let q = |interpolation| nd.quantile(0.4, interpolation).unwrap();
+        assert!((q(Interpolation::Linear) - 2.2).abs() < 1e-12);
+        assert_eq!(q(Interpolation::Lower), 2.0);
+        assert_eq!(q(Interpolation::Higher), 3.0);
+        assert_eq!(q(Interpolation::Nearest), 2.0);
+        assert_eq!(q(Interpolation::Midpoint), 2.5);
+
+        assert_eq!(nd.quantile(0.0, Interpolation::Linear), Ok(1.0));
+        assert_eq!(nd.quantile(1.0, Interpolation::Linear), Ok(4.0));
+        assert_eq!(nd.quantile(0.5, Interpolation::Linear), nd.median());
+        // position 1.5 is a tie between indices 1 and 2
+        assert_eq!(nd.quantile(0.5, Interpolation::Nearest), Ok(3.0));
+    }
+
+    #[test]
+    fn statisticstoolkit_quantile_errors() {
+        let a_sample = vec![1.0, 2.0, 3.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.quantile(1.5, Interpolation::Linear), Err(StatisticsError::InvalidQuantile(1.5)));
+        assert!(nd.quantile(f64::NAN, Interpolation::Linear).is_err());
+        assert_eq!(nd.percentile(-5.0, Interpolation::Linear), Err(StatisticsError::InvalidQuantile(-0.05)));
+        assert!(StatisticsToolkit::<f64>::new(&[]).quantile(0.5, Interpolation::Linear).is_err());
+        assert_eq!(StatisticsToolkit::new(&[7_i16]).quantile(0.9, Interpolation::Linear), Ok(7.0));
+    }
+
+    #[test]
+    fn statisticstoolkit_percentiles_and_iqr() {
+        // the numpy sample from the lib tests, checked against numpy.percentile
+        let a_sample = vec![
+            43.67221552, 46.40865622, 43.44603147, 43.16162571, 40.94815816,
+            44.585914, 45.84833022, 37.77765835, 40.23715928, 48.08791899,
+            44.80964938, 42.13753315, 38.80713956, 39.16183586, 42.61511209,
+            42.25099062, 41.2240736, 44.59644304, 41.27516889, 36.21238554,
+        ];
+        let nd = StatisticsToolkit::new(&a_sample);
+        let close = |a: f64, b: f64| assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+        close(nd.percentile(25.0, Interpolation::Linear).unwrap(), 40.77040844);
+        close(nd.percentile(50.0, Interpolation::Linear).unwrap(), 42.433051355);
+        close(nd.percentile(75.0, Interpolation::Linear).unwrap(), 44.58854626);
+        close(nd.iqr().unwrap(), 44.58854626 - 40.77040844);
+    }
+
+    #[test]
+    fn statisticstoolkit_percentile_errors() {
+        let too_few = Err(StatisticsError::TooFewValues { statistic: "quantile", required: 1, actual: 0 });
+        let empty: [f64; 0] = [];
+        assert_eq!(StatisticsToolkit::new(&empty).percentile(50.0, Interpolation::Linear), too_few);
+
+        let all_nan = [f64::NAN, f64::NAN];
+        let nd = StatisticsToolkit::new(&all_nan).with_nan_policy(NanPolicy::Omit);
+        assert_eq!(nd.percentile(50.0, Interpolation::Linear), too_few);
+
+        let nd = StatisticsToolkit::new(&[1.0, 2.0]);
+        assert_eq!(nd.percentile(150.0, Interpolation::Linear), Err(StatisticsError::InvalidQuantile(1.5)));
+    }
+
+    #[test]
+    fn statisticstoolkit_quantile_nan_policy() {
+        let a_sample = vec![3.0, f64::NAN, 1.0, 2.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert!(nd.quantile(0.5, Interpolation::Linear).unwrap().is_nan());
+        assert!(nd.iqr().unwrap().is_nan());
+        assert!(nd.mode().unwrap().is_nan());
+
+        let nd = nd.with_nan_policy(NanPolicy::Omit);
+        assert_eq!(nd.quantile(1.0, Interpolation::Linear), Ok(3.0));
+        assert_eq!(nd.iqr(), Ok(1.0));
+    }
+
+    #[test]
+    fn statisticstoolkit_mode() {
+        let a_sample = vec![3.0, 1.0, 3.0, 2.0, 1.0, 0.5];
+        assert_eq!(StatisticsToolkit::new(&a_sample).mode(), Ok(1.0));
+        let a_sample = vec![0.0, -0.0, 7.0, 7.0, 1.0];
+        assert_eq!(StatisticsToolkit::new(&a_sample).mode(), Ok(0.0));
+        let a_sample = vec![5.0, 4.0, 6.0];
+        assert_eq!(StatisticsToolkit::new(&a_sample).mode(), Ok(4.0));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{Interpolation, NanPolicy, Numeric, StatisticsError, StatisticsToolkit};
+
+///
+/// Multiplying the median absolute deviation by this makes it estimate
+/// the standard deviation of normally distributed data.
+///
+pub const MAD_NORMAL_SCALE: f64 = 1.482_602_218_505_602;
+
+impl<'a, T> StatisticsToolkit<'a, T> where T: Numeric {
+    ///
+    /// Computes the median absolute deviation from the median, a spread
+    /// that a few outliers can't inflate (see `MAD_NORMAL_SCALE`)
+    ///
+    pub fn mad(&self) -> Result<f64, StatisticsError> {
+        let median = self.median()?;
+        let deviations: Vec<f64> = self.values().map(|v| (v - median).abs()).collect();
+        StatisticsToolkit::new(&deviations).with_nan_policy(self.nan_policy).median()
+    }
+    ///
+    /// Computes the mean without the `proportion` smallest and the
+    /// `proportion` largest values, rounding the count to cut down
+    ///
+    pub fn trimmed_mean(&self, proportion: f64) -> Result<f64, StatisticsError> {
+        let (sorted, cut) = match self.sorted_with_cut("trimmed mean", proportion)? {
+            Some(sorted_cut) => sorted_cut,
+            None => return Ok(f64::NAN),
+        };
+        StatisticsToolkit::new(&sorted[cut..sorted.len() - cut]).mean()
+    }
+    ///
+    /// Computes the mean after replacing the `proportion` smallest and
+    /// the `proportion` largest values with the nearest remaining ones
+    ///
+    pub fn winsorized_mean(&self, proportion: f64) -> Result<f64, StatisticsError> {
+        let (mut sorted, cut) = match self.sorted_with_cut("winsorized mean", proportion)? {
+            Some(sorted_cut) => sorted_cut,
+            None => return Ok(f64::NAN),
+        };
+        let n = sorted.len();
+        let (low, high) = (sorted[cut], sorted[n - 1 - cut]);
+        sorted[..cut].fill(low);
+        sorted[n - cut..].fill(high);
+        StatisticsToolkit::new(&sorted).mean()
+    }
+
+    fn sorted_with_cut(
+        &self,
+        statistic: &'static str,
+        proportion: f64,
+    ) -> Result<Option<(Vec<f64>, usize)>, StatisticsError> {
+        if !(0.0..0.5).contains(&proportion) {
+            return Err(StatisticsError::InvalidProportion(proportion));
+        }
+        let n = self.require(statistic, 1)?;
+        let cut = (n as f64 * proportion).floor() as usize;
+        Ok(self.sorted().map(|sorted| (sorted, cut)))
+    }
+    ///
+    /// The indices of the values more than `threshold` (population)
+    /// standard deviations away from the mean. Nothing is flagged if all
+    /// values are equal.
+    ///
+    /// NaN values are an error unless the NaN policy omits them.
+    ///
+    pub fn zscore_outliers(&self, threshold: f64) -> Result<Vec<usize>, StatisticsError> {
+        self.require("z-score outliers", 1)?;
+        let (mean, std) = (self.mean()?, self.std()?);
+        self.outliers(|v| (v - mean).abs() > threshold * std)
+    }
+    ///
+    /// The indices of the values outside Tukey's fences, i.e. more than
+    /// `k` interquartile ranges below the first or above the third
+    /// quartile. `k` is commonly 1.5, or 3 for "far out" values.
+    ///
+    /// NaN values are an error unless the NaN policy omits them.
+    ///
+    pub fn iqr_outliers(&self, k: f64) -> Result<Vec<usize>, StatisticsError> {
+        self.require("IQR outliers", 1)?;
+        let q1 = self.quantile(0.25, Interpolation::Linear)?;
+        let q3 = self.quantile(0.75, Interpolation::Linear)?;
+        let (low, high) = (q1 - k * (q3 - q1), q3 + k * (q3 - q1));
+        self.outliers(|v| v < low || v > high)
+    }
+    ///
+    /// The indices into the base slice of the values `is_outlier` flags
+    ///
+    fn outliers<F: Fn(f64) -> bool>(&self, is_outlier: F) -> Result<Vec<usize>, StatisticsError> {
+        let values = self.base.iter().map(|v| v.to_f64());
+        if self.nan_policy == NanPolicy::Propagate && values.clone().any(f64::is_nan) {
+            return Err(StatisticsError::NonFiniteValue);
+        }
+        Ok(values
+            .enumerate()
+            .filter(|(_, v)| !v.is_nan() && is_outlier(*v))
+            .map(|(i, _)| i)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    // temperature readings in °C with two glitches
+    const READINGS: [f32; 12] = [21.5, 21.7, 21.6, 21.4, 85.0, 21.8, 21.5, 21.6, -40.0, 21.7, 21.9, 21.6];
+
+    fn close(a: Result<f64, StatisticsError>, b: f64) {
+        let a = a.unwrap();
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn statisticstoolkit_mad() {
+        let a_sample = vec![1.0, 1.0, 2.0, 2.0, 4.0, 6.0, 9.0];
+        close(StatisticsToolkit::new(&a_sample).mad(), 1.0);
+
+        let nd = StatisticsToolkit::new(&READINGS);
+        assert!(nd.mad().unwrap() < 0.2);
+        assert!(nd.std().unwrap() > 25.0);
+        assert!(StatisticsToolkit::<f64>::new(&[]).mad().is_err());
+    }
+
+    #[test]
+    fn statisticstoolkit_trimmed_and_winsorized_mean() {
+        let a_sample = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        close(nd.trimmed_mean(0.0), 14.5);
+        close(nd.trimmed_mean(0.1), 5.5);
+        close(nd.trimmed_mean(0.25), 5.5);
+        // 1 and 100 are replaced with 2 and 9
+        close(nd.winsorized_mean(0.1), 5.5);
+        close(nd.winsorized_mean(0.05), 14.5);
+
+        assert_eq!(nd.trimmed_mean(0.5), Err(StatisticsError::InvalidProportion(0.5)));
+        assert_eq!(nd.winsorized_mean(-0.1), Err(StatisticsError::InvalidProportion(-0.1)));
+
+        let nd = StatisticsToolkit::new(&READINGS);
+        // the f32 readings are only close to their decimal values
+        assert!((nd.trimmed_mean(0.1).unwrap() - 21.63).abs() < 1e-6);
+    }
+
+    #[test]
+    fn statisticstoolkit_zscore_outliers() {
+        let nd = StatisticsToolkit::new(&READINGS);
+        assert_eq!(nd.zscore_outliers(2.0), Ok(vec![4, 8]));
+        assert_eq!(nd.zscore_outliers(3.0), Ok(vec![]));
+        assert_eq!(StatisticsToolkit::new(&[5, 5, 5]).zscore_outliers(0.0), Ok(vec![]));
+    }
+
+    #[test]
+    fn statisticstoolkit_iqr_outliers() {
+        let nd = StatisticsToolkit::new(&READINGS);
+        assert_eq!(nd.iqr_outliers(1.5), Ok(vec![4, 8]));
+        assert_eq!(nd.iqr_outliers(3.0), Ok(vec![4, 8]));
+
+        let a_sample = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 20.0];
+        assert_eq!(StatisticsToolkit::new(&a_sample).iqr_outliers(1.5), Ok(vec![9]));
+        assert_eq!(StatisticsToolkit::new(&a_sample).iqr_outliers(3.0), Ok(vec![]));
+    }
+
+    #[test]
+    fn statisticstoolkit_outliers_nan_policy() {
+        let a_sample = vec![1.0, f64::NAN, 1.1, 0.9, 1.0, 50.0];
+        let nd = StatisticsToolkit::new(&a_sample);
+        assert_eq!(nd.iqr_outliers(1.5), Err(StatisticsError::NonFiniteValue));
+        assert!(nd.mad().unwrap().is_nan());
+
+        let nd = nd.with_nan_policy(NanPolicy::Omit);
+        // indices still refer to the original slice
+        assert_eq!(nd.iqr_outliers(1.5), Ok(vec![5]));
+        assert_eq!(nd.zscore_outliers(1.5), Ok(vec![5]));
+        close(nd.mad(), 0.1);
+        close(nd.trimmed_mean(0.2), 1.0333333333333334);
+    }
+}
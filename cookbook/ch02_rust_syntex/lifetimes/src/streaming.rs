@@ -0,0 +1,266 @@
+use std::iter::FromIterator;
+
+///
+/// Statistics over a stream of values, updated one value at a time
+/// with Welford's algorithm instead of keeping the values around.
+///
+/// Accumulators filled on different threads can be combined with
+/// `merge`, which gives the same result as feeding all values into one.
+///
+/// NaN values are counted like any other value, so they turn the mean
+/// and variance into NaN; `min` and `max` skip them.
+///
+/// # Example
+///
+/// ```
+/// use lifetimes::OnlineStatistics;
+///
+/// let mut stats = OnlineStatistics::new();
+/// for value in &[1.0, 2.0, 1.0] {
+///     stats.push(*value);
+/// }
+/// assert_eq!(stats.count(), 3);
+/// assert_eq!(stats.mean(), Some(1.3333333333333333));
+/// assert_eq!(stats.max(), Some(2.0));
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnlineStatistics {
+    count: u64,
+    mean: f64,
+    // sum of squared differences from the mean
+    m2: f64,
+    // over the values that aren't NaN
+    min: Option<f64>,
+    max: Option<f64>,
+}
+impl OnlineStatistics {
+    ///
+    /// Create an accumulator that hasn't seen any values
+    ///
+    pub fn new() -> OnlineStatistics {
+        OnlineStatistics {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: None,
+            max: None,
+        }
+    }
+    ///
+    /// Adds a value
+    ///
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        if !value.is_nan() {
+            self.min = Some(self.min.map_or(value, |m| m.min(value)));
+            self.max = Some(self.max.map_or(value, |m| m.max(value)));
+        }
+    }
+    ///
+    /// Combines the values seen by `other` into this accumulator
+    /// (Chan et al.'s parallel variant of Welford's algorithm)
+    ///
+    pub fn merge(&mut self, other: &OnlineStatistics) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+
+        self.mean += delta * weight;
+        self.m2 += other.m2 + delta * delta * self.count as f64 * weight;
+        self.count = count;
+        self.min = other.min.into_iter().chain(self.min).reduce(f64::min);
+        self.max = other.max.into_iter().chain(self.max).reduce(f64::max);
+    }
+    ///
+    /// The number of values seen so far
+    ///
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+    ///
+    /// The arithmetic mean, if there were any values
+    ///
+    pub fn mean(&self) -> Option<f64> {
+        self.seen(self.mean)
+    }
+    ///
+    /// The variance, dividing by the number of values like
+    /// `StatisticsToolkit::var`
+    ///
+    pub fn var(&self) -> Option<f64> {
+        self.seen(self.m2 / self.count as f64)
+    }
+    ///
+    /// The standard deviation
+    ///
+    pub fn std(&self) -> Option<f64> {
+        self.var().map(f64::sqrt)
+    }
+    ///
+    /// The sample variance, dividing by the number of values - 1 like
+    /// `StatisticsToolkit::sample_var`, if there were at least two values
+    ///
+    pub fn sample_var(&self) -> Option<f64> {
+        if self.count > 1 {
+            Some(self.m2 / (self.count - 1) as f64)
+        } else {
+            None
+        }
+    }
+    ///
+    /// The sample standard deviation
+    ///
+    pub fn sample_std(&self) -> Option<f64> {
+        self.sample_var().map(f64::sqrt)
+    }
+    ///
+    /// The smallest value that isn't NaN
+    ///
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+    ///
+    /// The largest value that isn't NaN
+    ///
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    fn seen(&self, value: f64) -> Option<f64> {
+        if self.count > 0 {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+impl Default for OnlineStatistics {
+    fn default() -> Self {
+        OnlineStatistics::new()
+    }
+}
+impl Extend<f64> for OnlineStatistics {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+impl FromIterator<f64> for OnlineStatistics {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = OnlineStatistics::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatisticsToolkit;
+    use std::thread;
+
+    fn assert_close(a: Option<f64>, b: f64) {
+        let a = a.unwrap();
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn online_statistics_empty() {
+        let stats = OnlineStatistics::new();
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.var(), None);
+        assert_eq!(stats.std(), None);
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.max(), None);
+    }
+
+    #[test]
+    fn online_statistics_match_toolkit() {
+        let values = vec![1.0, 1.0, 5.0, -3.5, 12.25, 7.0];
+        let stats: OnlineStatistics = values.iter().copied().collect();
+        let toolkit = StatisticsToolkit::new(&values);
+
+        assert_eq!(stats.count(), 6);
+        assert_close(stats.mean(), toolkit.mean().unwrap());
+        assert_close(stats.var(), toolkit.var().unwrap());
+        assert_close(stats.std(), toolkit.std().unwrap());
+        assert_close(stats.sample_var(), toolkit.sample_var().unwrap());
+        assert_eq!(stats.min(), Some(-3.5));
+        assert_eq!(stats.max(), Some(12.25));
+    }
+
+    #[test]
+    fn online_statistics_single_value() {
+        let mut stats = OnlineStatistics::new();
+        stats.push(f64::INFINITY);
+        assert_eq!(stats.max(), Some(f64::INFINITY));
+        assert_eq!(stats.min(), Some(f64::INFINITY));
+
+        let stats: OnlineStatistics = vec![4.0].into_iter().collect();
+        assert_eq!(stats.var(), Some(0.0));
+        assert_eq!(stats.sample_var(), None);
+    }
+
+    #[test]
+    fn online_statistics_are_stable_for_large_offsets() {
+        // a naive sum of squares loses all precision here
+        let stats: OnlineStatistics = (0..1000).map(|i| 1e9 + (i % 2) as f64).collect();
+        assert_close(stats.mean(), 1e9 + 0.5);
+        assert_close(stats.var(), 0.25);
+    }
+
+    #[test]
+    fn online_statistics_nan() {
+        let stats: OnlineStatistics = vec![1.0, f64::NAN, 3.0].into_iter().collect();
+        assert_eq!(stats.count(), 3);
+        assert!(stats.mean().unwrap().is_nan());
+        assert_eq!(stats.min(), Some(1.0));
+        assert_eq!(stats.max(), Some(3.0));
+
+        let stats: OnlineStatistics = vec![f64::NAN].into_iter().collect();
+        assert_eq!(stats.min(), None);
+    }
+
+    #[test]
+    fn online_statistics_merge() {
+        let values: Vec<f64> = (0..1000).map(|i| ((i * 37) % 101) as f64 / 7.0).collect();
+        let all: OnlineStatistics = values.iter().copied().collect();
+
+        let handles: Vec<_> = values
+            .chunks(300)
+            .map(|chunk| {
+                let chunk = chunk.to_vec();
+                thread::spawn(move || chunk.into_iter().collect::<OnlineStatistics>())
+            })
+            .collect();
+        let mut merged = OnlineStatistics::new();
+        for handle in handles {
+            merged.merge(&handle.join().unwrap());
+        }
+
+        assert_eq!(merged.count(), all.count());
+        assert_close(merged.mean(), all.mean().unwrap());
+        assert_close(merged.var(), all.var().unwrap());
+        assert_eq!(merged.min(), all.min());
+        assert_eq!(merged.max(), all.max());
+
+        let mut empty = OnlineStatistics::new();
+        empty.merge(&OnlineStatistics::new());
+        assert_eq!(empty.count(), 0);
+        merged.merge(&empty);
+        assert_eq!(merged.count(), 1000);
+    }
+}
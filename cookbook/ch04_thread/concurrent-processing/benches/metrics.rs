@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate criterion;
+use concurrent_processing::{
+    mae, mae_sequential, mse, mse_sequential, r_squared, r_squared_sequential, MetricsError,
+};
+use criterion::{black_box, Criterion, ParameterizedBenchmark};
+use rand::prelude::*;
+use std::cell::RefCell;
+
+// the sequential versions win on small inputs, where splitting the work
+// across threads costs more than it saves; compare them at growing sizes
+const SIZES: [usize; 5] = [100, 1_000, 10_000, 100_000, 1_000_000];
+thread_local!(static ITEMS: RefCell<(Vec<f32>, Vec<f32>)> = {
+    let y_values: (Vec<f32>, Vec<f32>) = (0..SIZES[SIZES.len() - 1])
+        .map(|_| (random::<f32>() + 1.0, random::<f32>() + 1.0))
+        .unzip();
+    RefCell::new(y_values)
+});
+
+type Metric = fn(&[f32], &[f32]) -> Result<f32, MetricsError>;
+
+fn bench_metric(c: &mut Criterion, name: &str, sequential: Metric, parallel: Metric) {
+    let run = |metric: Metric| {
+        move |b: &mut criterion::Bencher, size: &usize| {
+            ITEMS.with(|y_values| {
+                let y_borrowed = y_values.borrow();
+                let (y, y_predicted) = (&y_borrowed.0[..*size], &y_borrowed.1[..*size]);
+                b.iter(|| black_box(metric(y, y_predicted)))
+            })
+        }
+    };
+    c.bench(
+        name,
+        ParameterizedBenchmark::new("sequential", run(sequential), SIZES.to_vec())
+            .with_function("parallel", run(parallel)),
+    );
+}
+
+fn bench_mae(c: &mut Criterion) {
+    bench_metric(c, "MAE", mae_sequential, mae);
+}
+
+fn bench_mse(c: &mut Criterion) {
+    bench_metric(c, "MSE", mse_sequential, mse);
+}
+
+fn bench_r_squared(c: &mut Criterion) {
+    bench_metric(c, "R squared", r_squared_sequential, r_squared);
+}
+
+criterion_group!(benches, bench_mae, bench_mse, bench_r_squared);
+
+criterion_main!(benches);
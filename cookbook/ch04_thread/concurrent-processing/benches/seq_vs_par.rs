@@ -1,6 +1,6 @@
 #[macro_use]
 extern crate criterion;
-use concurrent_processing::{ssqe, ssqe_sequential, seq_count_alpha_nums, par_count_alpha_nums};
+use concurrent_processing::{ssqe, ssqe_sequential, seq_count_alpha_nums, par_count_alpha_nums, seq_text_stats, par_text_stats};
 use criterion::{black_box, Criterion};
 use std::cell::RefCell;
 use rand::prelude::*;
@@ -19,6 +19,15 @@ thread_local!(static CHARS: RefCell<String> = {
     RefCell::new(items)
 });
 
+const MAX_WORDS: usize = 100_000;
+thread_local!(static WORDS: RefCell<String> = {
+    let words = ["lorem", "ipsum", "dolor", "sit", "amet,", "consectetur", "adipiscing", "elit."];
+    let items: String = (0..MAX_WORDS)
+        .map(|i| format!("{}{}", words[random::<usize>() % words.len()], if i % 12 == 11 { "\n" } else { " " }))
+        .collect();
+    RefCell::new(items)
+});
+
 fn bench_count_seq(c: &mut Criterion) {
     c.bench_function("Counting in sequence", |b| {
         CHARS.with(|item| b.iter(|| black_box(seq_count_alpha_nums(&item.borrow()))))
@@ -31,6 +40,17 @@ fn bench_count_par(c: &mut Criterion) {
     });
 }
 
+fn bench_text_seq(c: &mut Criterion) {
+    c.bench_function("Text statistics in sequence", |b| {
+        WORDS.with(|item| b.iter(|| black_box(seq_text_stats(&item.borrow(), 2))))
+    });
+}
+
+fn bench_text_par(c: &mut Criterion) {
+    c.bench_function("Text statistics in parallel", |b| {
+        WORDS.with(|item| b.iter(|| black_box(par_text_stats(&item.borrow(), 2))))
+    });
+}
 
 fn bench_seq(c: &mut Criterion) {
     c.bench_function("Sequential vector operation", |b| {
@@ -50,6 +70,6 @@ fn bench_par(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_seq, bench_par,bench_count_par, bench_count_seq);
+criterion_group!(benches, bench_seq, bench_par,bench_count_par, bench_count_seq, bench_text_seq, bench_text_par);
 
 criterion_main!(benches);
\ No newline at end of file
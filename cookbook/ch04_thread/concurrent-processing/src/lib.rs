@@ -1,5 +1,14 @@
 use rayon::prelude::*;
 
+mod metrics;
+pub use metrics::{
+    mae, mae_sequential, mape, mape_sequential, max_error, max_error_sequential, mse, mse_sequential,
+    r_squared, r_squared_sequential, rmse, rmse_sequential, MetricsError,
+};
+
+mod text;
+pub use text::{par_text_stats, par_text_stats_dir, seq_text_stats, seq_text_stats_dir, TextStats};
+
 ///
 /// Sum of squared errors, a statistical error measure that squares and sums up the differences between predicted values and their ground truths.
 ///
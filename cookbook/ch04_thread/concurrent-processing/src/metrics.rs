@@ -0,0 +1,251 @@
+//!
+//! Error metrics comparing predictions with their ground truths, each in a
+//! parallel (rayon) and a sequential version. Sums are accumulated in `f64`
+//! so both versions agree closely despite summing in different orders.
+//!
+
+use rayon::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+///
+/// Why a metric couldn't be computed
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricsError {
+    /// There must be exactly one prediction per ground truth
+    LengthMismatch { y: usize, y_predicted: usize },
+    /// There are no values to compare
+    Empty,
+    /// MAPE divides by the ground truths, so none may be 0
+    ZeroGroundTruth { index: usize },
+    /// R² is undefined if all ground truths are equal
+    ConstantGroundTruth,
+}
+impl fmt::Display for MetricsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricsError::LengthMismatch { y, y_predicted } => write!(
+                f,
+                "{} ground truths but {} predictions",
+                y, y_predicted
+            ),
+            MetricsError::Empty => write!(f, "no values to compare"),
+            MetricsError::ZeroGroundTruth { index } => {
+                write!(f, "ground truth at index {} is 0", index)
+            }
+            MetricsError::ConstantGroundTruth => write!(f, "all ground truths are equal"),
+        }
+    }
+}
+impl Error for MetricsError {}
+
+fn check(y: &[f32], y_predicted: &[f32]) -> Result<f64, MetricsError> {
+    if y.len() != y_predicted.len() {
+        Err(MetricsError::LengthMismatch { y: y.len(), y_predicted: y_predicted.len() })
+    } else if y.is_empty() {
+        Err(MetricsError::Empty)
+    } else {
+        Ok(y.len() as f64)
+    }
+}
+
+fn abs_error((y, y_pred): (&f32, &f32)) -> f64 {
+    (*y as f64 - *y_pred as f64).abs()
+}
+
+fn squared_error((y, y_pred): (&f32, &f32)) -> f64 {
+    (*y as f64 - *y_pred as f64).powi(2)
+}
+
+///
+/// Mean absolute error
+///
+pub fn mae(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    let sum: f64 = y.par_iter().zip(y_predicted).map(abs_error).sum();
+    Ok((sum / n) as f32)
+}
+
+pub fn mae_sequential(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    let sum: f64 = y.iter().zip(y_predicted).map(abs_error).sum();
+    Ok((sum / n) as f32)
+}
+
+///
+/// Mean squared error, `ssqe` divided by the number of values
+///
+pub fn mse(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    let sum: f64 = y.par_iter().zip(y_predicted).map(squared_error).sum();
+    Ok((sum / n) as f32)
+}
+
+pub fn mse_sequential(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    let sum: f64 = y.iter().zip(y_predicted).map(squared_error).sum();
+    Ok((sum / n) as f32)
+}
+
+///
+/// Root mean squared error, in the same unit as the values
+///
+pub fn rmse(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    mse(y, y_predicted).map(f32::sqrt)
+}
+
+pub fn rmse_sequential(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    mse_sequential(y, y_predicted).map(f32::sqrt)
+}
+
+///
+/// Mean absolute percentage error, as a fraction (0.1 is 10 %)
+///
+pub fn mape(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    if let Some(index) = y.par_iter().position_first(|y| *y == 0.0) {
+        return Err(MetricsError::ZeroGroundTruth { index });
+    }
+    let sum: f64 = y
+        .par_iter()
+        .zip(y_predicted)
+        .map(|pair| abs_error(pair) / (*pair.0 as f64).abs())
+        .sum();
+    Ok((sum / n) as f32)
+}
+
+pub fn mape_sequential(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    if let Some(index) = y.iter().position(|y| *y == 0.0) {
+        return Err(MetricsError::ZeroGroundTruth { index });
+    }
+    let sum: f64 = y
+        .iter()
+        .zip(y_predicted)
+        .map(|pair| abs_error(pair) / (*pair.0 as f64).abs())
+        .sum();
+    Ok((sum / n) as f32)
+}
+
+///
+/// Coefficient of determination, 1 for perfect predictions and 0 for
+/// always predicting the mean (it is negative for worse predictions)
+///
+pub fn r_squared(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    let mean = y.par_iter().map(|y| *y as f64).sum::<f64>() / n;
+    let ss_total: f64 = y.par_iter().map(|y| (*y as f64 - mean).powi(2)).sum();
+    let ss_residual: f64 = y.par_iter().zip(y_predicted).map(squared_error).sum();
+    r_squared_from(ss_residual, ss_total)
+}
+
+pub fn r_squared_sequential(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    let n = check(y, y_predicted)?;
+    let mean = y.iter().map(|y| *y as f64).sum::<f64>() / n;
+    let ss_total: f64 = y.iter().map(|y| (*y as f64 - mean).powi(2)).sum();
+    let ss_residual: f64 = y.iter().zip(y_predicted).map(squared_error).sum();
+    r_squared_from(ss_residual, ss_total)
+}
+
+fn r_squared_from(ss_residual: f64, ss_total: f64) -> Result<f32, MetricsError> {
+    if ss_total == 0.0 {
+        Err(MetricsError::ConstantGroundTruth)
+    } else {
+        Ok((1.0 - ss_residual / ss_total) as f32)
+    }
+}
+
+///
+/// The largest absolute error
+///
+pub fn max_error(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    check(y, y_predicted)?;
+    let max = y.par_iter().zip(y_predicted).map(abs_error).reduce(|| 0.0, f64::max);
+    Ok(max as f32)
+}
+
+pub fn max_error_sequential(y: &[f32], y_predicted: &[f32]) -> Result<f32, MetricsError> {
+    check(y, y_predicted)?;
+    let max = y.iter().zip(y_predicted).map(abs_error).fold(0.0, f64::max);
+    Ok(max as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Metric = fn(&[f32], &[f32]) -> Result<f32, MetricsError>;
+
+    const METRICS: [(&str, Metric, Metric); 6] = [
+        ("mae", mae, mae_sequential),
+        ("mse", mse, mse_sequential),
+        ("rmse", rmse, rmse_sequential),
+        ("mape", mape, mape_sequential),
+        ("r_squared", r_squared, r_squared_sequential),
+        ("max_error", max_error, max_error_sequential),
+    ];
+
+    #[test]
+    fn test_metrics() {
+        let y = [3.0, -0.5, 2.0, 7.0];
+        let y_predicted = [2.5, 0.0, 2.0, 8.0];
+        // the same values as scikit-learn's examples for these metrics
+        let expected = [0.5, 0.375, 0.612_372_4, 0.327_380_97, 0.948_608_1, 1.0];
+
+        for ((name, par, seq), expected) in METRICS.iter().zip(&expected) {
+            let result = par(&y, &y_predicted).unwrap();
+            assert!((result - expected).abs() < 1e-6, "{}: {}", name, result);
+            assert_eq!(seq(&y, &y_predicted), Ok(result), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_metrics_perfect_predictions() {
+        let y = [1.0, 2.0, 3.0];
+        assert_eq!(mae(&y, &y), Ok(0.0));
+        assert_eq!(rmse(&y, &y), Ok(0.0));
+        assert_eq!(r_squared(&y, &y), Ok(1.0));
+        assert_eq!(max_error_sequential(&y, &y), Ok(0.0));
+    }
+
+    #[test]
+    fn test_metrics_errors() {
+        for (name, par, seq) in METRICS.iter() {
+            let mismatch = Err(MetricsError::LengthMismatch { y: 3, y_predicted: 2 });
+            assert_eq!(par(&[1.0, 2.0, 3.0], &[1.0, 2.0]), mismatch, "{}", name);
+            assert_eq!(seq(&[1.0, 2.0, 3.0], &[1.0, 2.0]), mismatch, "{}", name);
+            assert_eq!(par(&[], &[]), Err(MetricsError::Empty), "{}", name);
+            assert_eq!(seq(&[], &[]), Err(MetricsError::Empty), "{}", name);
+        }
+
+        let zero = Err(MetricsError::ZeroGroundTruth { index: 1 });
+        assert_eq!(mape(&[1.0, 0.0, 0.0], &[1.0, 1.0, 1.0]), zero);
+        assert_eq!(mape_sequential(&[1.0, 0.0, 0.0], &[1.0, 1.0, 1.0]), zero);
+        assert_eq!(r_squared(&[2.0, 2.0], &[1.0, 2.0]), Err(MetricsError::ConstantGroundTruth));
+        assert_eq!(
+            r_squared_sequential(&[2.0, 2.0], &[1.0, 2.0]),
+            Err(MetricsError::ConstantGroundTruth)
+        );
+    }
+
+    #[test]
+    fn test_metrics_error_messages() {
+        assert_eq!(
+            MetricsError::LengthMismatch { y: 3, y_predicted: 2 }.to_string(),
+            "3 ground truths but 2 predictions"
+        );
+        assert_eq!(MetricsError::ZeroGroundTruth { index: 4 }.to_string(), "ground truth at index 4 is 0");
+    }
+
+    #[test]
+    fn test_metrics_parallel_matches_sequential() {
+        let y: Vec<f32> = (0..100_000).map(|i| ((i * 7919) % 1000) as f32 / 10.0 + 1.0).collect();
+        let y_predicted: Vec<f32> = y.iter().enumerate().map(|(i, y)| y + (i % 13) as f32 - 6.0).collect();
+
+        for (name, par, seq) in METRICS.iter() {
+            let (par, seq) = (par(&y, &y_predicted).unwrap(), seq(&y, &y_predicted).unwrap());
+            assert!((par - seq).abs() <= 1e-6 * seq.abs().max(1.0), "{}: {} != {}", name, par, seq);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//!
+//! Word, n-gram and size statistics of a text, with a parallel (rayon)
+//! version and a sequential reference that produces the same output.
+//!
+//! Words are the whitespace separated tokens with leading and trailing
+//! non-alphanumeric characters removed, in lower case, so `"Hello,"` and
+//! `"hello"` are the same word and a lone `"--"` is no word at all.
+//! N-grams are runs of `n` consecutive words, joined by a single space.
+//!
+
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+///
+/// Statistics of a text, or of several merged texts
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextStats {
+    /// Lines, counted like `str::lines`
+    pub lines: usize,
+    /// Words, i.e. the sum of `word_counts`
+    pub words: usize,
+    pub chars: usize,
+    pub bytes: usize,
+    pub word_counts: HashMap<String, usize>,
+    pub ngram_counts: HashMap<String, usize>,
+}
+impl TextStats {
+    ///
+    /// Adds the statistics of another text. N-grams spanning both texts
+    /// are not counted.
+    ///
+    pub fn merge(&mut self, other: TextStats) {
+        self.lines += other.lines;
+        self.words += other.words;
+        self.chars += other.chars;
+        self.bytes += other.bytes;
+        merge_counts(&mut self.word_counts, other.word_counts);
+        merge_counts(&mut self.ngram_counts, other.ngram_counts);
+    }
+
+    ///
+    /// The `k` most frequent words, ties ordered alphabetically
+    ///
+    pub fn top_words(&self, k: usize) -> Vec<(&str, usize)> {
+        top(&self.word_counts, k)
+    }
+
+    ///
+    /// The `k` most frequent n-grams, ties ordered alphabetically
+    ///
+    pub fn top_ngrams(&self, k: usize) -> Vec<(&str, usize)> {
+        top(&self.ngram_counts, k)
+    }
+}
+
+fn normalize(token: &str) -> Option<String> {
+    let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+    if word.is_empty() {
+        None
+    } else {
+        Some(word.to_lowercase())
+    }
+}
+
+fn count(counts: &mut HashMap<String, usize>, key: &str) {
+    match counts.get_mut(key) {
+        Some(n) => *n += 1,
+        None => {
+            counts.insert(key.to_string(), 1);
+        }
+    }
+}
+
+fn merge_counts(into: &mut HashMap<String, usize>, mut from: HashMap<String, usize>) {
+    if from.len() > into.len() {
+        std::mem::swap(into, &mut from);
+    }
+    for (key, n) in from {
+        *into.entry(key).or_insert(0) += n;
+    }
+}
+
+fn top(counts: &HashMap<String, usize>, k: usize) -> Vec<(&str, usize)> {
+    let mut top: Vec<(&str, usize)> = counts.iter().map(|(key, n)| (key.as_str(), *n)).collect();
+    top.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    top.truncate(k);
+    top
+}
+
+///
+/// Statistics of `corpus` with n-grams of `n` words (none if `n` is 0), in sequence
+///
+pub fn seq_text_stats(corpus: &str, n: usize) -> TextStats {
+    let words: Vec<String> = corpus.split_whitespace().filter_map(normalize).collect();
+
+    let mut word_counts = HashMap::new();
+    for word in &words {
+        count(&mut word_counts, word);
+    }
+    let mut ngram_counts = HashMap::new();
+    if n > 0 {
+        for ngram in words.windows(n) {
+            count(&mut ngram_counts, &ngram.join(" "));
+        }
+    }
+
+    TextStats {
+        lines: corpus.lines().count(),
+        words: words.len(),
+        chars: corpus.chars().count(),
+        bytes: corpus.len(),
+        word_counts,
+        ngram_counts,
+    }
+}
+
+///
+/// Statistics of `corpus` with n-grams of `n` words (none if `n` is 0), in parallel
+///
+pub fn par_text_stats(corpus: &str, n: usize) -> TextStats {
+    let words: Vec<String> = corpus.par_split_whitespace().filter_map(normalize).collect();
+
+    let merged = |mut a, b| {
+        merge_counts(&mut a, b);
+        a
+    };
+    let word_counts = words
+        .par_iter()
+        .fold(HashMap::new, |mut counts, word| {
+            count(&mut counts, word);
+            counts
+        })
+        .reduce(HashMap::new, merged);
+    let ngram_counts = if n > 0 {
+        words
+            .par_windows(n)
+            .fold(HashMap::new, |mut counts, ngram| {
+                count(&mut counts, &ngram.join(" "));
+                counts
+            })
+            .reduce(HashMap::new, merged)
+    } else {
+        HashMap::new()
+    };
+
+    TextStats {
+        lines: corpus.par_lines().count(),
+        words: words.len(),
+        chars: corpus.par_chars().count(),
+        bytes: corpus.len(),
+        word_counts,
+        ngram_counts,
+    }
+}
+
+///
+/// All files below `dir`, in sorted order
+///
+fn files_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+///
+/// The merged statistics of every file below `dir`, one after the other.
+/// Fails if a file can't be read or isn't UTF-8.
+///
+pub fn seq_text_stats_dir<P: AsRef<Path>>(dir: P, n: usize) -> io::Result<TextStats> {
+    let mut stats = TextStats::default();
+    for file in files_in(dir.as_ref())? {
+        stats.merge(seq_text_stats(&fs::read_to_string(file)?, n));
+    }
+    Ok(stats)
+}
+
+///
+/// The merged statistics of every file below `dir`, reading and analyzing
+/// the files in parallel. Fails if a file can't be read or isn't UTF-8.
+///
+pub fn par_text_stats_dir<P: AsRef<Path>>(dir: P, n: usize) -> io::Result<TextStats> {
+    files_in(dir.as_ref())?
+        .par_iter()
+        .map(|file| fs::read_to_string(file).map(|text| par_text_stats(&text, n)))
+        .try_reduce(TextStats::default, |mut a, b| {
+            a.merge(b);
+            Ok(a)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: &str = "The cat sat on the mat.\n\
+                          The cat -- a black one -- sat!\r\n\
+                          \n\
+                          Über cats: THE END";
+
+    fn corpus(lines: usize) -> String {
+        let words = ["alpha", "Beta,", "gamma!", "(delta)", "alpha", "épsilon", "--", "beta"];
+        (0..lines)
+            .map(|i| {
+                let line: Vec<&str> = (0..i % 11).map(|j| words[(i * 7 + j * 3) % words.len()]).collect();
+                line.join(if i % 2 == 0 { " " } else { "\t " })
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_text_stats() {
+        let stats = seq_text_stats(CORPUS, 2);
+        assert_eq!(stats.lines, 4);
+        assert_eq!(stats.words, 16);
+        assert_eq!(stats.chars, CORPUS.chars().count());
+        assert_eq!(stats.bytes, CORPUS.len());
+        assert_eq!(stats.word_counts["the"], 4);
+        assert_eq!(stats.word_counts["über"], 1);
+        assert_eq!(stats.word_counts.get("--"), None);
+        assert_eq!(stats.word_counts.values().sum::<usize>(), stats.words);
+        assert_eq!(stats.ngram_counts["the cat"], 2);
+        assert_eq!(stats.ngram_counts["sat über"], 1);
+        assert_eq!(stats.ngram_counts.values().sum::<usize>(), stats.words - 1);
+
+        assert_eq!(stats.top_words(3), vec![("the", 4), ("cat", 2), ("sat", 2)]);
+        assert_eq!(stats.top_ngrams(1), vec![("the cat", 2)]);
+        assert_eq!(stats.top_words(100).len(), stats.word_counts.len());
+    }
+
+    #[test]
+    fn test_text_stats_edge_cases() {
+        assert_eq!(seq_text_stats("", 2), TextStats::default());
+        assert_eq!(par_text_stats("", 2), TextStats::default());
+        assert!(seq_text_stats(CORPUS, 0).ngram_counts.is_empty());
+        assert!(par_text_stats("one two", 3).ngram_counts.is_empty());
+        assert_eq!(par_text_stats(CORPUS, 1).ngram_counts, seq_text_stats(CORPUS, 1).word_counts);
+    }
+
+    #[test]
+    fn test_text_stats_parallel_matches_sequential() {
+        let corpus = corpus(5_000);
+        for n in 0..4 {
+            assert_eq!(par_text_stats(&corpus, n), seq_text_stats(&corpus, n), "n = {}", n);
+        }
+        assert_eq!(par_text_stats(CORPUS, 3), seq_text_stats(CORPUS, 3));
+    }
+
+    #[test]
+    fn test_text_stats_dir() {
+        let dir = std::env::temp_dir().join(format!("text-stats-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.txt"), CORPUS).unwrap();
+        fs::write(dir.join("nested").join("b.txt"), corpus(100)).unwrap();
+
+        let par = par_text_stats_dir(&dir, 2).unwrap();
+        let seq = seq_text_stats_dir(&dir, 2).unwrap();
+        let mut expected = seq_text_stats(CORPUS, 2);
+        expected.merge(seq_text_stats(&corpus(100), 2));
+
+        fs::write(dir.join("binary"), [0xff, 0xfe]).unwrap();
+        let invalid = par_text_stats_dir(&dir, 2).unwrap_err().kind();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(par, seq);
+        assert_eq!(seq, expected);
+        assert_eq!(invalid, io::ErrorKind::InvalidData);
+        assert!(seq_text_stats_dir(&dir, 2).is_err());
+    }
+}